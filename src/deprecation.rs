@@ -0,0 +1,41 @@
+use std::fmt::Display;
+
+///Marks a method, field, global instance or type as deprecated
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(
+    all(feature = "mlua", feature = "derive"),
+    derive(crate::mlu::FromToLua, crate::ToTypename)
+)]
+#[cfg_attr(
+    all(feature = "mlua", feature = "derive"),
+    tealr(tealr_name = crate)
+)]
+pub struct Deprecation {
+    ///why it got deprecated, and what to use instead
+    pub reason: String,
+    ///the version in which it got deprecated
+    pub since: Option<String>,
+}
+
+impl Deprecation {
+    ///creates a new deprecation marker
+    pub fn new(reason: &str, since: Option<&str>) -> Self {
+        Self {
+            reason: reason.to_owned(),
+            since: since.map(ToOwned::to_owned),
+        }
+    }
+}
+
+impl Display for Deprecation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("deprecated")?;
+        if let Some(since) = &self.since {
+            write!(f, " since {}", since)?;
+        }
+        if !self.reason.is_empty() {
+            write!(f, ": {}", self.reason)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,521 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::{
+    type_to_teal_parts, Deprecation, EnumGenerator, EnumRepresentation, ExportedFunction,
+    ExtraPage, Field, FunctionDocs, GlobalInstance, NamePart, RecordGenerator, Stability,
+    TaggedUnionGenerator, TaggedVariant, ToLuaLiteral, Type, TypeGenerator, TypeWalker,
+};
+
+mod html;
+mod markdown;
+
+pub use markdown::MarkdownPage;
+
+///A piece of lua code found in the documentation, see [TypeWalker::lua_examples]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LuaExample {
+    ///the type the example belongs to, `None` for global instances
+    pub owner: Option<String>,
+    ///the member or global instance the example belongs to, `None` if it documents the type itself
+    pub member: Option<String>,
+    ///the lua code of the example
+    pub code: String,
+}
+
+impl LuaExample {
+    ///where the example got found, like `Player.teleport`
+    pub fn location(&self) -> String {
+        match (&self.owner, &self.member) {
+            (Some(owner), Some(member)) => format!("{}.{}", owner, member),
+            (Some(x), None) | (None, Some(x)) => x.clone(),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+///every fenced code block marked as lua in the given documentation.
+///
+///Fences may be indented and have more text after the language, like ` ```lua title `.
+fn lua_code_blocks(doc: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    //the code and how far the fence of it is indented
+    let mut current: Option<(String, usize)> = None;
+    for line in doc.lines() {
+        let trimmed = line.trim_start_matches([' ', '\t']);
+        match &mut current {
+            Some((code, _)) if trimmed.starts_with("```") => {
+                blocks.push(std::mem::take(code));
+                current = None;
+            }
+            Some((code, indent)) => {
+                //the indentation of the fence isn't part of the code
+                let strip = (line.len() - trimmed.len()).min(*indent);
+                code.push_str(&line[strip..]);
+                code.push('\n');
+            }
+            None => {
+                let language = trimmed
+                    .strip_prefix("```")
+                    .and_then(|info| info.split_whitespace().next());
+                if language == Some("lua") {
+                    current = Some((String::new(), line.len() - trimmed.len()));
+                }
+            }
+        }
+    }
+    blocks
+}
+
+///A single documented item, like a field, method or global instance
+pub(crate) struct DocItem<'a> {
+    pub(crate) name: String,
+    pub(crate) signature: Vec<NamePart>,
+    ///deprecation, since and stability, already rendered as text
+    pub(crate) metadata: Vec<String>,
+    pub(crate) doc: &'a str,
+    pub(crate) function_docs: Option<&'a FunctionDocs>,
+}
+
+///A group of items of the same kind, like every method of a type
+pub(crate) struct DocSection<'a> {
+    pub(crate) title: &'static str,
+    ///what a single item of this section is, like `method`
+    pub(crate) kind: &'static str,
+    pub(crate) items: Vec<DocItem<'a>>,
+}
+
+fn metadata(
+    deprecated: Option<&Deprecation>,
+    since: Option<&str>,
+    stability: Stability,
+) -> Vec<String> {
+    let mut metadata = Vec::new();
+    if let Some(deprecation) = deprecated {
+        metadata.push(deprecation.to_string());
+    }
+    if let Some(since) = since {
+        metadata.push(format!("since: {}", since));
+    }
+    if stability != Stability::Stable {
+        metadata.push(format!("stability: {}", stability));
+    }
+    metadata
+}
+
+fn with_value(ty: &Type, value: Option<&String>) -> Vec<NamePart> {
+    let mut signature = type_to_teal_parts(ty, false).into_owned();
+    if let Some(value) = value {
+        signature.push(NamePart::symbol(format!(" = {}", value)));
+    }
+    signature
+}
+
+fn field_item<'a>(record: &'a RecordGenerator, field: &'a Field) -> DocItem<'a> {
+    DocItem {
+        name: field.name.to_string(),
+        signature: with_value(&field.ty, field.value.as_ref()),
+        metadata: metadata(
+            field.deprecated.as_ref(),
+            field.since.as_deref(),
+            field.stability,
+        ),
+        doc: record
+            .documentation
+            .get(&field.name)
+            .map(String::as_str)
+            .unwrap_or_default(),
+        function_docs: None,
+    }
+}
+
+///how the variants of a tagged union are stored, with names of fields in backticks
+pub(crate) fn representation_description(representation: &EnumRepresentation) -> String {
+    match representation {
+        EnumRepresentation::InternallyTagged(tag) => {
+            format!("A table whose `{}` field names the variant it is.", tag)
+        }
+        EnumRepresentation::AdjacentlyTagged(tag, content) => format!(
+            "A table whose `{}` field names the variant and whose `{}` field holds its value.",
+            tag, content
+        ),
+        EnumRepresentation::ExternallyTagged => "A table with a single field, named after the variant, that holds its value. Variants without a value are stored as just their name.".to_owned(),
+        EnumRepresentation::Untagged => "The value of one of the variants below, which are tried in order. Variants without a value are stored as `nil`.".to_owned(),
+    }
+}
+
+///the fields of a variant of a tagged union, starting with the tag field.
+///
+///Fields stored inside another field are prefixed with its name, like `content.radius`
+pub(crate) fn variant_items<'a>(
+    union: &'a TaggedUnionGenerator,
+    variant: &'a TaggedVariant,
+) -> Vec<DocItem<'a>> {
+    let item = |name: String, signature: Vec<NamePart>| DocItem {
+        name,
+        signature,
+        metadata: Vec::new(),
+        doc: "",
+        function_docs: None,
+    };
+    //where the tag and the value or fields of the variant are stored, and whether the fields are nested in the value
+    let (tag, payload, nested) = match &union.representation {
+        EnumRepresentation::InternallyTagged(tag) => (Some(tag.as_str()), None, false),
+        EnumRepresentation::AdjacentlyTagged(tag, content) => {
+            (Some(tag.as_str()), Some(content.as_str()), true)
+        }
+        EnumRepresentation::ExternallyTagged => (None, Some(variant.tag_value.as_str()), true),
+        EnumRepresentation::Untagged => (None, Some("value"), false),
+    };
+    let mut items = Vec::new();
+    if let Some(tag) = tag {
+        items.push(item(
+            tag.to_owned(),
+            vec![NamePart::symbol(variant.tag_value.to_lua_literal())],
+        ));
+    }
+    if let (Some(payload), Some(ty)) = (payload, &variant.value) {
+        items.push(item(
+            payload.to_owned(),
+            type_to_teal_parts(ty, false).into_owned(),
+        ));
+    }
+    let prefix = match (payload, nested) {
+        (Some(payload), true) => format!("{}.", payload),
+        _ => String::new(),
+    };
+    items.extend(variant.fields.iter().map(|field| {
+        DocItem {
+            name: format!("{}{}", prefix, field.name),
+            signature: with_value(&field.ty, field.value.as_ref()),
+            metadata: metadata(
+                field.deprecated.as_ref(),
+                field.since.as_deref(),
+                field.stability,
+            ),
+            doc: variant
+                .documentation
+                .get(&field.name)
+                .map(String::as_str)
+                .unwrap_or_default(),
+            function_docs: None,
+        }
+    }));
+    items
+}
+
+fn function_item<'a>(record: &'a RecordGenerator, function: &'a ExportedFunction) -> DocItem<'a> {
+    let mut signature = Vec::new();
+    //meta methods are shown the way teal declares them, to make clear what operator they are for
+    if function.is_meta_method {
+        signature.push(NamePart::symbol(format!("metamethod {}: ", function.name)));
+    }
+    signature.extend(
+        type_to_teal_parts(&function.into_type(), false)
+            .iter()
+            .cloned(),
+    );
+    DocItem {
+        name: function.name.to_string(),
+        signature,
+        metadata: metadata(
+            function.deprecated.as_ref(),
+            function.since.as_deref(),
+            function.stability,
+        ),
+        doc: record
+            .documentation
+            .get(&function.name)
+            .map(String::as_str)
+            .unwrap_or_default(),
+        function_docs: Some(&function.docs),
+    }
+}
+
+pub(crate) fn global_item(global: &GlobalInstance) -> DocItem<'_> {
+    DocItem {
+        name: global.full_name(),
+        signature: with_value(&global.ty, global.value.as_ref()),
+        metadata: metadata(
+            global.deprecated.as_ref(),
+            global.since.as_deref(),
+            global.stability,
+        ),
+        doc: &global.doc,
+        function_docs: None,
+    }
+}
+
+///The global instances of a single namespace, see [namespaces]
+pub(crate) struct Namespace<'a> {
+    ///the full name of the namespace, like `game.physics`. Empty for the instances that aren't part of one
+    pub(crate) name: String,
+    ///how many tables deep the namespace is, 0 for the instances that aren't part of one
+    pub(crate) depth: usize,
+    pub(crate) instances: Vec<&'a GlobalInstance>,
+}
+
+///groups the instances by their namespace. Every namespace directly follows the one it is nested in,
+///which is also listed when it has no instances of its own
+pub(crate) fn namespaces(instances: &[GlobalInstance]) -> Vec<Namespace<'_>> {
+    let mut grouped = BTreeMap::<&[String], Vec<&GlobalInstance>>::new();
+    for instance in instances {
+        for depth in 1..instance.namespace.len() {
+            grouped.entry(&instance.namespace[..depth]).or_default();
+        }
+        grouped
+            .entry(&instance.namespace)
+            .or_default()
+            .push(instance);
+    }
+    grouped
+        .into_iter()
+        .map(|(namespace, instances)| Namespace {
+            name: namespace.join("."),
+            depth: namespace.len(),
+            instances,
+        })
+        .collect()
+}
+
+///every documented member of a record, grouped by kind. Empty sections are left out
+pub(crate) fn record_sections(record: &RecordGenerator) -> Vec<DocSection<'_>> {
+    let mut sections = Vec::new();
+    for (title, kind, fields) in [
+        ("Fields", "field", &record.fields),
+        ("Static fields", "static field", &record.field_functions),
+        ("Meta fields", "meta field", &record.static_fields),
+    ] {
+        let mut items: Vec<DocItem> = Vec::new();
+        for field in fields {
+            //fields with both a getter and a setter get added twice
+            if !items.iter().any(|v| v.name == field.name.to_string()) {
+                items.push(field_item(record, field));
+            }
+        }
+        sections.push(DocSection { title, kind, items });
+    }
+    if let Some(constructor) = &record.constructor {
+        sections.push(DocSection {
+            title: "Constructor",
+            kind: "constructor",
+            items: vec![function_item(record, constructor)],
+        });
+    }
+    for (title, kind, functions) in [
+        ("Methods", "method", [&record.methods, &record.mut_methods]),
+        (
+            "Functions",
+            "function",
+            [&record.functions, &record.mut_functions],
+        ),
+        (
+            "Meta methods",
+            "meta method",
+            [&record.meta_method, &record.meta_method_mut],
+        ),
+        (
+            "Meta functions",
+            "meta function",
+            [&record.meta_function, &record.meta_function_mut],
+        ),
+    ] {
+        let items = functions
+            .into_iter()
+            .flatten()
+            .filter(|function| !record.is_constructor(function))
+            .map(|function| function_item(record, function))
+            .collect();
+        sections.push(DocSection { title, kind, items });
+    }
+    if let Some(index) = &record.dynamic_index {
+        sections.push(DocSection {
+            title: "Dynamic index",
+            kind: "dynamic index",
+            items: vec![DocItem {
+                name: "[key]".to_owned(),
+                signature: type_to_teal_parts(&Type::Map(index.clone()), false).into_owned(),
+                metadata: Vec::new(),
+                doc: "Keys that are not one of the named members are looked up dynamically",
+                function_docs: None,
+            }],
+        });
+    }
+    sections.retain(|v| !v.items.is_empty());
+    sections
+}
+
+impl TypeWalker {
+    ///Collects the lua examples of every type, member and global instance.
+    ///
+    ///These are the examples added with [document_example](crate::mlu::TealDataMethods::document_example)
+    ///and every code block in the documentation that starts with ` ```lua `.
+    ///```
+    ///# use tealr::{RecordGenerator, ToTypename, TypeWalker};
+    ///#[derive(ToTypename)]
+    ///struct Example {}
+    ///let mut record = RecordGenerator::new::<Example>(false);
+    ///record.type_doc = "Some docs\n```lua\nprint('hi')\n```".to_owned();
+    ///let mut walker = TypeWalker::new();
+    ///walker.given_types.push(record.into());
+    ///let examples = walker.lua_examples();
+    ///assert_eq!(examples[0].location(), "Example");
+    ///assert_eq!(examples[0].code, "print('hi')\n");
+    ///```
+    pub fn lua_examples(&self) -> Vec<LuaExample> {
+        let mut examples = Vec::new();
+        let mut add = |owner: Option<&str>, member: Option<&str>, code: String| {
+            examples.push(LuaExample {
+                owner: owner.map(ToOwned::to_owned),
+                member: member.map(ToOwned::to_owned),
+                code,
+            })
+        };
+        for ty in self.iter() {
+            let name = type_name(ty);
+            match ty {
+                TypeGenerator::Record(record) => {
+                    for code in lua_code_blocks(&record.type_doc) {
+                        add(Some(&name), None, code);
+                    }
+                    for section in record_sections(record) {
+                        for item in section.items {
+                            let structured = item
+                                .function_docs
+                                .map(|v| v.examples.clone())
+                                .unwrap_or_default();
+                            for code in lua_code_blocks(item.doc).into_iter().chain(structured) {
+                                add(Some(&name), Some(&item.name), code);
+                            }
+                        }
+                    }
+                }
+                TypeGenerator::Enum(x) => {
+                    for code in lua_code_blocks(&x.type_doc) {
+                        add(Some(&name), None, code);
+                    }
+                    for variant in &x.variants {
+                        let doc = x.variant_docs.get(variant).map(String::as_str);
+                        for code in lua_code_blocks(doc.unwrap_or_default()) {
+                            add(Some(&name), Some(&variant.to_string()), code);
+                        }
+                    }
+                }
+                TypeGenerator::TaggedUnion(x) => {
+                    for code in lua_code_blocks(&x.type_doc) {
+                        add(Some(&name), None, code);
+                    }
+                    for variant in &x.variants {
+                        let variant_name = variant.name.to_string();
+                        for code in lua_code_blocks(&variant.doc) {
+                            add(Some(&name), Some(&variant_name), code);
+                        }
+                        for item in variant_items(x, variant) {
+                            for code in lua_code_blocks(item.doc) {
+                                add(
+                                    Some(&name),
+                                    Some(&format!("{}.{}", variant_name, item.name)),
+                                    code,
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        let globals = self
+            .global_instances_off
+            .iter()
+            .chain(self.modules.iter().flat_map(|v| v.instances.iter()));
+        for global in globals {
+            for code in lua_code_blocks(&global.doc) {
+                add(None, Some(&global.full_name()), code);
+            }
+        }
+        examples
+    }
+}
+
+///the name a type is documented under
+pub(crate) fn type_name(ty: &TypeGenerator) -> String {
+    match ty {
+        TypeGenerator::Record(x) => match &x.ty {
+            Type::Single(x) => x.name.to_string(),
+            x => crate::type_to_string(x, false),
+        },
+        TypeGenerator::Enum(EnumGenerator { name, .. })
+        | TypeGenerator::TaggedUnion(TaggedUnionGenerator { name, .. }) => name.clone(),
+    }
+}
+
+///turns a name into something that can safely be used as a file name
+pub(crate) fn file_stem(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+///The pages that make up the documentation of a [TypeWalker], shared by every output format
+pub(crate) struct DocSite<'a> {
+    pub(crate) walker: &'a TypeWalker,
+    ///every type that gets its own page, together with its name and the stem of its file name
+    pub(crate) types: Vec<(String, String, &'a TypeGenerator)>,
+    ///every extra page, together with the stem of its file name
+    pub(crate) pages: Vec<(&'a ExtraPage, String)>,
+    ///the file stem of the first documented type with a given name, used to link to it
+    links: HashMap<String, String>,
+}
+
+impl<'a> DocSite<'a> {
+    pub(crate) fn new(walker: &'a TypeWalker) -> Self {
+        //file systems can be case insensitive, and `index` is the stem of the index page
+        let mut used = HashSet::from(["index".to_owned()]);
+        let mut unique_stem = |stem: String| {
+            let mut candidate = stem.clone();
+            let mut count = 1;
+            while !used.insert(candidate.to_lowercase()) {
+                count += 1;
+                candidate = format!("{}_{}", stem, count);
+            }
+            candidate
+        };
+        //types and pages that share a name still get a page each
+        let types = walker
+            .iter()
+            .map(|v| {
+                let name = type_name(v);
+                let stem = unique_stem(file_stem(&name));
+                (name, stem, v)
+            })
+            .collect::<Vec<_>>();
+        let mut links = HashMap::new();
+        for (name, stem, _) in &types {
+            links.entry(name.clone()).or_insert_with(|| stem.clone());
+        }
+        let pages = walker
+            .extra_page
+            .iter()
+            .map(|extra| {
+                (
+                    extra,
+                    unique_stem(format!("page_{}", file_stem(&extra.name))),
+                )
+            })
+            .collect();
+        Self {
+            walker,
+            types,
+            pages,
+            links,
+        }
+    }
+    ///the file stem of the page documenting the given type, if there is one
+    pub(crate) fn link(&self, type_name: &str) -> Option<&str> {
+        self.links.get(type_name).map(String::as_str)
+    }
+}
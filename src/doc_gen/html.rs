@@ -0,0 +1,386 @@
+use std::{fs, io, path::Path};
+
+use pulldown_cmark::{Event, Options, Parser};
+
+use crate::{
+    EnumGenerator, FunctionDocs, GlobalInstance, KindOfType, NamePart, RecordGenerator,
+    TaggedUnionGenerator, TypeGenerator, TypeWalker,
+};
+
+use super::{
+    file_stem, global_item, namespaces, record_sections, representation_description, variant_items,
+    DocItem, DocSite,
+};
+
+const PAGE_TEMPLATE: &str = include_str!("html/page.html");
+const STYLE: &str = include_str!("html/style.css");
+const SEARCH_SCRIPT: &str = include_str!("html/search.js");
+
+///A single entry of `search_index.json`
+#[derive(serde::Serialize)]
+struct SearchEntry {
+    name: String,
+    kind: &'static str,
+    signature: String,
+    page: String,
+    anchor: Option<String>,
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn anchor(kind: &str, name: &str) -> String {
+    format!("{}.{}", file_stem(kind), file_stem(name))
+}
+
+fn signature(site: &DocSite, parts: &[NamePart]) -> String {
+    let mut html = String::from("<div class=\"signature\">");
+    for part in parts {
+        match part {
+            NamePart::Type(ty) => {
+                let name = escape(&ty.name);
+                match (&ty.type_kind, site.link(&ty.name)) {
+                    (KindOfType::External, Some(stem)) => {
+                        html.push_str(&format!(
+                            "<a class=\"type\" href=\"{}.html\">{}</a>",
+                            stem, name
+                        ));
+                    }
+                    (KindOfType::Builtin, _) => {
+                        html.push_str(&format!("<span class=\"builtin\">{}</span>", name))
+                    }
+                    (KindOfType::Generic, _) => {
+                        html.push_str(&format!("<span class=\"generic\">{}</span>", name))
+                    }
+                    (KindOfType::External, None) => {
+                        html.push_str(&format!("<span class=\"type\">{}</span>", name))
+                    }
+                }
+            }
+            NamePart::Symbol(x) => html.push_str(&escape(x)),
+        }
+    }
+    html.push_str("</div>\n");
+    html
+}
+
+fn plain_signature(parts: &[NamePart]) -> String {
+    parts.iter().map(|v| v.as_ref_str().as_ref()).collect()
+}
+
+///renders the markdown used by documentation comments. Raw html in them is shown as text
+fn doc(text: &str) -> String {
+    let text = text.trim();
+    if text.is_empty() {
+        return String::new();
+    }
+    let events = Parser::new_ext(text, Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH).map(
+        |event| match event {
+            Event::Html(x) | Event::InlineHtml(x) => Event::Text(x),
+            event => event,
+        },
+    );
+    let mut html = String::from("<div class=\"doc\">\n");
+    pulldown_cmark::html::push_html(&mut html, events);
+    html.push_str("</div>\n");
+    html
+}
+
+fn function_docs(docs: &FunctionDocs) -> String {
+    let mut html = String::new();
+    if !docs.params.is_empty() {
+        html.push_str("<h4>Parameters</h4>\n<ul>\n");
+        for param in &docs.params {
+            html.push_str(&format!(
+                "<li><code>{}</code>: {}</li>\n",
+                escape(&param.name),
+                escape(&param.doc)
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+    for (title, list) in [("Returns", &docs.returns), ("Errors", &docs.errors)] {
+        if !list.is_empty() {
+            html.push_str(&format!("<h4>{}</h4>\n<ul>\n", title));
+            for item in list {
+                html.push_str(&format!("<li>{}</li>\n", escape(item)));
+            }
+            html.push_str("</ul>\n");
+        }
+    }
+    for example in &docs.examples {
+        html.push_str(&format!(
+            "<h4>Example</h4>\n<pre><code class=\"language-lua\">{}</code></pre>\n",
+            escape(example.trim_end())
+        ));
+    }
+    html
+}
+
+fn metadata(metadata: &[String]) -> String {
+    metadata
+        .iter()
+        .map(|v| format!("<p class=\"metadata\">{}</p>\n", escape(v)))
+        .collect()
+}
+
+fn item(site: &DocSite, kind: &str, item: &DocItem) -> String {
+    leveled_item(site, 3, kind, item)
+}
+
+///renders an item with a heading of the given level, so it can be nested under other headings
+fn leveled_item(site: &DocSite, level: usize, kind: &str, item: &DocItem) -> String {
+    let level = level.min(6);
+    let mut html = format!(
+        "<h{level} id=\"{}\">{}</h{level}>\n",
+        anchor(kind, &item.name),
+        escape(&item.name)
+    );
+    html.push_str(&signature(site, &item.signature));
+    html.push_str(&metadata(&item.metadata));
+    html.push_str(&doc(item.doc));
+    if let Some(docs) = item.function_docs {
+        html.push_str(&function_docs(docs));
+    }
+    html
+}
+
+fn page(title: &str, content: &str) -> String {
+    PAGE_TEMPLATE
+        .replace("{title}", &escape(title))
+        .replace("{content}", content)
+}
+
+fn record_page(
+    site: &DocSite,
+    name: &str,
+    stem: &str,
+    record: &RecordGenerator,
+    search: &mut Vec<SearchEntry>,
+) -> String {
+    let mut html = format!("<h1>{}</h1>\n", escape(name));
+    html.push_str(&metadata(&super::metadata(
+        record.deprecated.as_ref(),
+        record.since.as_deref(),
+        record.stability,
+    )));
+    html.push_str(&doc(&record.type_doc));
+    for section in record_sections(record) {
+        html.push_str(&format!("<h2>{}</h2>\n", section.title));
+        for x in &section.items {
+            html.push_str(&item(site, section.kind, x));
+            search.push(SearchEntry {
+                name: format!("{}.{}", name, x.name),
+                kind: section.kind,
+                signature: plain_signature(&x.signature),
+                page: format!("{}.html", stem),
+                anchor: Some(anchor(section.kind, &x.name)),
+            });
+        }
+    }
+    html
+}
+
+fn enum_page(name: &str, enumeration: &EnumGenerator) -> String {
+    let mut html = format!("<h1>{}</h1>\n", escape(name));
+    html.push_str(&doc(&enumeration.type_doc));
+    html.push_str("<h2>Variants</h2>\n<ul>\n");
+    for variant in &enumeration.variants {
+        html.push_str(&format!(
+            "<li><code>{}</code>",
+            escape(&variant.to_string())
+        ));
+        if let Some(doc) = enumeration.variant_docs.get(variant) {
+            html.push_str(&format!(": {}", escape(doc.trim())));
+        }
+        html.push_str("</li>\n");
+    }
+    html.push_str("</ul>\n");
+    html
+}
+
+fn tagged_union_page(
+    site: &DocSite,
+    name: &str,
+    stem: &str,
+    union: &TaggedUnionGenerator,
+    search: &mut Vec<SearchEntry>,
+) -> String {
+    let mut html = format!("<h1>{}</h1>\n", escape(name));
+    html.push_str(&doc(&union.type_doc));
+    //the names of fields are in backticks, which become code tags
+    let description = representation_description(&union.representation)
+        .split('`')
+        .enumerate()
+        .map(|(i, part)| match i % 2 {
+            0 => escape(part),
+            _ => format!("<code>{}</code>", escape(part)),
+        })
+        .collect::<String>();
+    html.push_str(&format!("<p>{}</p>\n", description));
+    for variant in &union.variants {
+        let variant_name = variant.name.to_string();
+        html.push_str(&format!("<h2>{}</h2>\n", escape(&variant_name)));
+        html.push_str(&doc(&variant.doc));
+        for x in &variant_items(union, variant) {
+            html.push_str(&item(site, &variant_name, x));
+            search.push(SearchEntry {
+                name: format!("{}.{}", variant_name, x.name),
+                kind: "field",
+                signature: plain_signature(&x.signature),
+                page: format!("{}.html", stem),
+                anchor: Some(anchor(&variant_name, &x.name)),
+            });
+        }
+    }
+    html
+}
+
+///the global instances, with the ones in a namespace nested under a heading of that namespace
+fn global_instances(
+    site: &DocSite,
+    instances: &[GlobalInstance],
+    search: &mut Vec<SearchEntry>,
+) -> String {
+    let mut html = String::new();
+    for namespace in namespaces(instances) {
+        if namespace.depth > 0 {
+            let level = (namespace.depth + 2).min(6);
+            html.push_str(&format!(
+                "<h{level}>{}</h{level}>\n",
+                escape(&namespace.name)
+            ));
+        }
+        for global in namespace.instances {
+            let x = global_item(global);
+            html.push_str(&leveled_item(site, namespace.depth + 3, "global", &x));
+            search.push(SearchEntry {
+                name: x.name.clone(),
+                kind: "global",
+                signature: plain_signature(&x.signature),
+                page: "index.html".to_owned(),
+                anchor: Some(anchor("global", &x.name)),
+            });
+        }
+    }
+    html
+}
+
+fn index_page(site: &DocSite, search: &mut Vec<SearchEntry>) -> String {
+    let walker = site.walker;
+    let mut html = String::from("<h1>Index</h1>\n");
+    if !site.types.is_empty() {
+        html.push_str("<h2>Types</h2>\n<ul>\n");
+        for (name, stem, _) in &site.types {
+            html.push_str(&format!(
+                "<li><a href=\"{}.html\">{}</a></li>\n",
+                stem,
+                escape(name)
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+    let mut globals = Vec::new();
+    if !walker.global_instances_off.is_empty() {
+        globals.push(("Globals".to_owned(), &walker.global_instances_off));
+    }
+    for module in &walker.modules {
+        globals.push((format!("Module {}", module.name), &module.instances));
+    }
+    for (title, instances) in globals {
+        html.push_str(&format!("<h2>{}</h2>\n", escape(&title)));
+        html.push_str(&global_instances(site, instances, search));
+    }
+    if let Some(std_lib) = &walker.available_std_lib {
+        html.push_str("<h2>Standard library</h2>\n<p>Only these parts of the lua standard library are available.</p>\n<ul>\n");
+        for entry in std_lib {
+            html.push_str(&format!("<li><code>{}</code></li>\n", escape(entry)));
+        }
+        html.push_str("</ul>\n");
+    }
+    if !site.pages.is_empty() {
+        html.push_str("<h2>Pages</h2>\n<ul>\n");
+        for (extra, stem) in &site.pages {
+            html.push_str(&format!(
+                "<li><a href=\"{}.html\">{}</a></li>\n",
+                stem,
+                escape(&extra.name)
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+    html
+}
+
+impl TypeWalker {
+    ///Writes a static website documenting every type, global instance, module and extra page to the given directory.
+    ///
+    ///The site consists of an `index.html` page, a page for every type and extra page, and a search index stored as `search_index.json`.
+    ///Every asset is embedded in tealr, so the site works offline and can be opened straight from the file system.
+    ///```no_run
+    ///# use tealr::TypeWalker;
+    ///TypeWalker::new().write_html("./docs").unwrap();
+    ///```
+    pub fn write_html(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let site = DocSite::new(self);
+        let mut search = Vec::new();
+        for (name, stem, ty) in &site.types {
+            let content = match ty {
+                TypeGenerator::Record(x) => record_page(&site, name, stem, x, &mut search),
+                TypeGenerator::Enum(x) => enum_page(name, x),
+                TypeGenerator::TaggedUnion(x) => {
+                    tagged_union_page(&site, name, stem, x, &mut search)
+                }
+            };
+            search.push(SearchEntry {
+                name: name.clone(),
+                kind: "type",
+                signature: String::new(),
+                page: format!("{}.html", stem),
+                anchor: None,
+            });
+            fs::write(dir.join(format!("{}.html", stem)), page(name, &content))?;
+        }
+        for (extra, stem) in &site.pages {
+            let content = format!("<h1>{}</h1>\n{}", escape(&extra.name), doc(&extra.content));
+            search.push(SearchEntry {
+                name: extra.name.clone(),
+                kind: "page",
+                signature: String::new(),
+                page: format!("{}.html", stem),
+                anchor: None,
+            });
+            fs::write(
+                dir.join(format!("{}.html", stem)),
+                page(&extra.name, &content),
+            )?;
+        }
+        let index = index_page(&site, &mut search);
+        fs::write(dir.join("index.html"), page("Index", &index))?;
+
+        let search = serde_json::to_string(&search)?;
+        fs::write(dir.join("search_index.json"), &search)?;
+        //browsers don't allow pages opened from the file system to fetch the json, so it is also stored as a script
+        fs::write(
+            dir.join("search_index.js"),
+            format!("window.searchIndex = {};\n", search),
+        )?;
+        fs::write(dir.join("style.css"), STYLE)?;
+        fs::write(dir.join("search.js"), SEARCH_SCRIPT)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+"use strict";
+(function () {
+    var input = document.getElementById("search");
+    var results = document.getElementById("search-results");
+    input.addEventListener("input", function () {
+        var query = input.value.toLowerCase();
+        results.innerHTML = "";
+        if (query.length === 0) {
+            return;
+        }
+        var found = window.searchIndex.filter(function (entry) {
+            return entry.name.toLowerCase().indexOf(query) !== -1;
+        });
+        found.slice(0, 50).forEach(function (entry) {
+            var item = document.createElement("li");
+            var link = document.createElement("a");
+            link.href = entry.page + (entry.anchor ? "#" + entry.anchor : "");
+            link.textContent = entry.name;
+            item.appendChild(link);
+            item.appendChild(document.createTextNode(" (" + entry.kind + ") " + entry.signature));
+            results.appendChild(item);
+        });
+    });
+})();
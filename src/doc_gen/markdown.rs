@@ -0,0 +1,265 @@
+use crate::{
+    EnumGenerator, FunctionDocs, GlobalInstance, KindOfType, NamePart, RecordGenerator,
+    TaggedUnionGenerator, TypeGenerator, TypeWalker,
+};
+
+use super::{
+    global_item, namespaces, record_sections, representation_description, variant_items, DocItem,
+    DocSite,
+};
+
+///A single page of the documentation created by [TypeWalker::to_markdown]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MarkdownPage {
+    ///the name of the file the page should be stored in. Links between pages are relative to each other
+    pub file_name: String,
+    ///the markdown content of the page
+    pub content: String,
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '#'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn signature(site: &DocSite, parts: &[NamePart]) -> String {
+    parts
+        .iter()
+        .map(|part| match part {
+            NamePart::Type(ty) if ty.type_kind == KindOfType::External => {
+                match site.link(&ty.name) {
+                    Some(stem) => format!("[{}]({}.md)", escape(&ty.name), stem),
+                    None => escape(&ty.name),
+                }
+            }
+            x => escape(x.as_ref_str()),
+        })
+        .collect()
+}
+
+fn function_docs(docs: &FunctionDocs) -> String {
+    let mut text = String::new();
+    if !docs.params.is_empty() {
+        text.push_str("**Parameters**\n\n");
+        for param in &docs.params {
+            text.push_str(&format!("- `{}`: {}\n", param.name, param.doc));
+        }
+        text.push('\n');
+    }
+    for (title, list) in [("Returns", &docs.returns), ("Errors", &docs.errors)] {
+        if !list.is_empty() {
+            text.push_str(&format!("**{}**\n\n", title));
+            for item in list {
+                text.push_str(&format!("- {}\n", item));
+            }
+            text.push('\n');
+        }
+    }
+    for example in &docs.examples {
+        text.push_str(&format!(
+            "**Example**\n\n```lua\n{}\n```\n\n",
+            example.trim_end()
+        ));
+    }
+    text
+}
+
+fn item(site: &DocSite, item: &DocItem) -> String {
+    leveled_item(site, 3, item)
+}
+
+///renders an item with a heading of the given level, so it can be nested under other headings
+fn leveled_item(site: &DocSite, level: usize, item: &DocItem) -> String {
+    let mut text = format!(
+        "{} {}\n\n{}\n\n",
+        "#".repeat(level.min(6)),
+        escape(&item.name),
+        signature(site, &item.signature)
+    );
+    for metadata in &item.metadata {
+        text.push_str(&format!("*{}*\n\n", escape(metadata)));
+    }
+    let doc = item.doc.trim();
+    if !doc.is_empty() {
+        text.push_str(doc);
+        text.push_str("\n\n");
+    }
+    if let Some(docs) = item.function_docs {
+        text.push_str(&function_docs(docs));
+    }
+    text
+}
+
+fn record_page(site: &DocSite, name: &str, record: &RecordGenerator) -> String {
+    let mut page = format!("# {}\n\n", escape(name));
+    for metadata in super::metadata(
+        record.deprecated.as_ref(),
+        record.since.as_deref(),
+        record.stability,
+    ) {
+        page.push_str(&format!("*{}*\n\n", escape(&metadata)));
+    }
+    let doc = record.type_doc.trim();
+    if !doc.is_empty() {
+        page.push_str(doc);
+        page.push_str("\n\n");
+    }
+    for section in record_sections(record) {
+        page.push_str(&format!("## {}\n\n", section.title));
+        for x in &section.items {
+            page.push_str(&item(site, x));
+        }
+    }
+    page
+}
+
+fn enum_page(name: &str, enumeration: &EnumGenerator) -> String {
+    let mut page = format!("# {}\n\n", escape(name));
+    let doc = enumeration.type_doc.trim();
+    if !doc.is_empty() {
+        page.push_str(doc);
+        page.push_str("\n\n");
+    }
+    page.push_str("## Variants\n\n");
+    for variant in &enumeration.variants {
+        match enumeration.variant_docs.get(variant) {
+            Some(doc) => page.push_str(&format!("- `{}`: {}\n", variant, doc.trim())),
+            None => page.push_str(&format!("- `{}`\n", variant)),
+        }
+    }
+    page
+}
+
+fn tagged_union_page(site: &DocSite, name: &str, union: &TaggedUnionGenerator) -> String {
+    let mut page = format!("# {}\n\n", escape(name));
+    let doc = union.type_doc.trim();
+    if !doc.is_empty() {
+        page.push_str(doc);
+        page.push_str("\n\n");
+    }
+    page.push_str(&representation_description(&union.representation));
+    page.push_str("\n\n");
+    for variant in &union.variants {
+        page.push_str(&format!("## {}\n\n", escape(&variant.name.to_string())));
+        let doc = variant.doc.trim();
+        if !doc.is_empty() {
+            page.push_str(doc);
+            page.push_str("\n\n");
+        }
+        for x in &variant_items(union, variant) {
+            page.push_str(&item(site, x));
+        }
+    }
+    page
+}
+
+///the global instances, with the ones in a namespace nested under a heading of that namespace
+fn global_instances(site: &DocSite, instances: &[GlobalInstance]) -> String {
+    let mut text = String::new();
+    for namespace in namespaces(instances) {
+        if namespace.depth > 0 {
+            text.push_str(&format!(
+                "{} {}\n\n",
+                "#".repeat((namespace.depth + 2).min(6)),
+                escape(&namespace.name)
+            ));
+        }
+        for global in namespace.instances {
+            text.push_str(&leveled_item(
+                site,
+                namespace.depth + 3,
+                &global_item(global),
+            ));
+        }
+    }
+    text
+}
+
+fn index_page(site: &DocSite) -> String {
+    let walker = site.walker;
+    let mut page = String::from("# Index\n\n");
+    if !site.types.is_empty() {
+        page.push_str("## Types\n\n");
+        for (name, stem, _) in &site.types {
+            page.push_str(&format!("- [{}]({}.md)\n", escape(name), stem));
+        }
+        page.push('\n');
+    }
+    if !walker.global_instances_off.is_empty() {
+        page.push_str("## Globals\n\n");
+        page.push_str(&global_instances(site, &walker.global_instances_off));
+    }
+    for module in &walker.modules {
+        page.push_str(&format!("## Module `{}`\n\n", module.name));
+        page.push_str(&global_instances(site, &module.instances));
+    }
+    if let Some(std_lib) = &walker.available_std_lib {
+        page.push_str("## Standard library\n\nOnly these parts of the lua standard library are available.\n\n");
+        for entry in std_lib {
+            page.push_str(&format!("- `{}`\n", entry));
+        }
+        page.push('\n');
+    }
+    if !site.pages.is_empty() {
+        page.push_str("## Pages\n\n");
+        for (extra, stem) in &site.pages {
+            page.push_str(&format!("- [{}]({}.md)\n", escape(&extra.name), stem));
+        }
+        page.push('\n');
+    }
+    page
+}
+
+impl TypeWalker {
+    ///Generates markdown documentation for every type, global instance, module and extra page.
+    ///
+    ///This creates an `index.md` page listing the types, global instances, modules and extra pages and a page for every type.
+    ///Types used in signatures link to their own page.
+    ///It is up to you to store the pages, all in the same directory.
+    ///```
+    ///# use tealr::{Field, RecordGenerator, ToTypename, TypeWalker};
+    ///#[derive(ToTypename)]
+    ///struct Example {}
+    ///let mut record = RecordGenerator::new::<Example>(false);
+    ///record.fields.push(Field::new::<Example>("copy"));
+    ///let mut walker = TypeWalker::new();
+    ///walker.given_types.push(record.into());
+    ///let pages = walker.to_markdown();
+    ///assert_eq!(pages[0].file_name, "index.md");
+    ///assert!(pages[1].content.contains("[Example](Example.md)"));
+    ///```
+    pub fn to_markdown(&self) -> Vec<MarkdownPage> {
+        let site = DocSite::new(self);
+        let mut pages = vec![MarkdownPage {
+            file_name: "index.md".to_owned(),
+            content: index_page(&site),
+        }];
+        for (name, stem, ty) in &site.types {
+            let content = match ty {
+                TypeGenerator::Record(x) => record_page(&site, name, x),
+                TypeGenerator::Enum(x) => enum_page(name, x),
+                TypeGenerator::TaggedUnion(x) => tagged_union_page(&site, name, x),
+            };
+            pages.push(MarkdownPage {
+                file_name: format!("{}.md", stem),
+                content,
+            });
+        }
+        for (extra, stem) in &site.pages {
+            pages.push(MarkdownPage {
+                file_name: format!("{}.md", stem),
+                content: extra.content.clone(),
+            });
+        }
+        pages
+    }
+}
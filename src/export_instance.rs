@@ -1,7 +1,10 @@
-use crate::{type_walker::GlobalInstance, ToTypename};
+use crate::{type_walker::GlobalInstance, Deprecation, Stability, ToTypename};
 
 pub(crate) struct InstanceWalker {
     doc: String,
+    deprecated: Option<Deprecation>,
+    since: Option<String>,
+    stability: Option<Stability>,
     pub(crate) instances: Vec<GlobalInstance>,
 }
 #[cfg(feature = "mlua")]
@@ -12,29 +15,64 @@ impl crate::mlu::InstanceCollector for InstanceWalker {
         T: ToTypename,
         F: FnOnce(&mlua::Lua) -> Result<T, mlua::Error>,
     {
-        self.add_instance::<T>(name.into());
+        self.add_instance::<T>(name.into(), None);
+        Ok(self)
+    }
+    fn add_constant<P, T>(&mut self, name: P, value: T) -> Result<&mut Self, mlua::Error>
+    where
+        P: Into<String>,
+        T: ToTypename + mlua::IntoLua + crate::ToLuaLiteral,
+    {
+        self.add_instance::<T>(name.into(), Some(value.to_lua_literal()));
         Ok(self)
     }
     fn document_instance(&mut self, doc: &'static str) -> &mut Self {
         self.document_instance(doc);
         self
     }
+    fn deprecate(&mut self, reason: &str, since: Option<&str>) -> &mut Self {
+        self.deprecated = Some(Deprecation::new(reason, since));
+        self
+    }
+    fn since(&mut self, version: &str) -> &mut Self {
+        self.since = Some(version.to_owned());
+        self
+    }
+    fn stability(&mut self, stability: Stability) -> &mut Self {
+        self.stability = Some(stability);
+        self
+    }
 }
 
 impl InstanceWalker {
     pub(crate) fn new() -> Self {
         Self {
             doc: Default::default(),
+            deprecated: None,
+            since: None,
+            stability: None,
             instances: Default::default(),
         }
     }
     #[allow(dead_code)]
-    fn add_instance<T: ToTypename>(&mut self, name: String) {
+    fn add_instance<T: ToTypename>(&mut self, name: String, value: Option<String>) {
         let doc = std::mem::take(&mut self.doc);
+        let (namespace, name) = match name.rsplit_once('.') {
+            Some((namespace, name)) => (
+                namespace.split('.').map(ToOwned::to_owned).collect(),
+                name.to_owned(),
+            ),
+            None => (Vec::new(), name),
+        };
         self.instances.push(GlobalInstance {
             name,
+            namespace,
             doc,
             ty: T::to_typename(),
+            value,
+            deprecated: self.deprecated.take(),
+            since: self.since.take(),
+            stability: self.stability.take().unwrap_or_default(),
         });
     }
     #[allow(dead_code)]
@@ -1,9 +1,113 @@
 use std::collections::HashSet;
 
 use crate::{
-    get_generic_types, get_generics, type_generator::NameContainer, FunctionParam, Name, Type,
+    get_generic_types, get_generics, type_generator::NameContainer, type_to_string, Deprecation,
+    FunctionParam, Name, Placement, Stability, Type,
 };
 
+///Documentation of a single parameter
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(
+    all(feature = "derive", feature = "mlua"),
+    derive(crate::mlu::FromToLua, crate::ToTypename)
+)]
+#[cfg_attr(
+    all(feature = "derive", feature = "mlua"),
+    tealr(tealr_name = crate)
+)]
+pub struct ParamDocs {
+    ///Name of the parameter
+    pub name: String,
+    ///The documentation of the parameter
+    pub doc: String,
+}
+
+///Structured documentation of a function, next to the free-form documentation stored in [RecordGenerator::documentation](crate::RecordGenerator#structfield.documentation)
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(
+    all(feature = "derive", feature = "mlua"),
+    derive(crate::mlu::FromToLua, crate::ToTypename)
+)]
+#[cfg_attr(
+    all(feature = "derive", feature = "mlua"),
+    tealr(tealr_name = crate)
+)]
+pub struct FunctionDocs {
+    ///Documentation of the parameters, in the order they got documented
+    pub params: Vec<ParamDocs>,
+    ///Documentation of the return values, in order
+    pub returns: Vec<String>,
+    ///The ways this function can fail
+    pub errors: Vec<String>,
+    ///Example code showing how to use the function
+    pub examples: Vec<String>,
+}
+
+impl FunctionDocs {
+    ///returns true if nothing got documented
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+            && self.returns.is_empty()
+            && self.errors.is_empty()
+            && self.examples.is_empty()
+    }
+    ///documents a parameter
+    pub fn document_param(&mut self, name: &str, documentation: &str) {
+        self.params.push(ParamDocs {
+            name: name.to_owned(),
+            doc: documentation.to_owned(),
+        })
+    }
+    ///documents the next return value
+    pub fn document_return(&mut self, documentation: &str) {
+        self.returns.push(documentation.to_owned())
+    }
+    ///documents a way the function can fail
+    pub fn document_error(&mut self, documentation: &str) {
+        self.errors.push(documentation.to_owned())
+    }
+    ///adds an example
+    pub fn document_example(&mut self, code: &str) {
+        self.examples.push(code.to_owned())
+    }
+    ///renders the documentation as the plain text used by `help()`
+    #[cfg(feature = "mlua")]
+    pub(crate) fn to_help_text(&self) -> String {
+        let mut text = String::new();
+        if !self.params.is_empty() {
+            text.push_str("\n\nparams:");
+            for param in &self.params {
+                text.push_str(&format!("\n  {}: {}", param.name, param.doc));
+            }
+        }
+        if !self.returns.is_empty() {
+            text.push_str("\n\nreturns:");
+            for ret in &self.returns {
+                text.push_str(&format!("\n  - {}", ret));
+            }
+        }
+        if !self.errors.is_empty() {
+            text.push_str("\n\nerrors:");
+            for error in &self.errors {
+                text.push_str(&format!("\n  - {}", error));
+            }
+        }
+        for example in &self.examples {
+            text.push_str("\n\nexample:\n");
+            text.push_str(example);
+        }
+        text
+    }
+}
+
+fn display_returns(returns: &[String]) -> String {
+    if returns.is_empty() {
+        "nothing".to_owned()
+    } else {
+        returns.join(", ")
+    }
+}
+
 ///Contains the data needed to write down the type of a function
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(
@@ -23,6 +127,21 @@ pub struct ExportedFunction {
     pub returns: Vec<Type>,
     ///If this function is a meta_method
     pub is_meta_method: bool,
+    ///Structured documentation of the parameters, return values, errors and examples
+    #[serde(default)]
+    pub docs: FunctionDocs,
+    ///Set if this function is deprecated
+    #[serde(default)]
+    pub deprecated: Option<Deprecation>,
+    ///The version in which this function got added
+    #[serde(default)]
+    pub since: Option<String>,
+    ///How stable this function is
+    #[serde(default)]
+    pub stability: Stability,
+    ///Whether a static function is documented on the type, its proxy or both
+    #[serde(default)]
+    pub placement: Placement,
 }
 impl ExportedFunction {
     ///turns the exported function into just its type representation
@@ -60,9 +179,194 @@ impl ExportedFunction {
             is_meta_method,
             params,
             returns: R::get_types(),
+            docs: Default::default(),
+            deprecated: None,
+            since: None,
+            stability: Stability::Stable,
+            placement: Placement::Both,
         }
     }
 
+    ///renders a meta method the way teal declares it, like `metamethod __add: function(Vector, Vector): Vector`.
+    ///
+    ///Returns `None` if this function is not a meta method
+    ///```
+    ///# use tealr::ExportedFunction;
+    ///let function = ExportedFunction::new::<(i64, i64), bool, _>("__eq", true, None);
+    ///assert_eq!(
+    ///    function.to_teal_metamethod().unwrap(),
+    ///    "metamethod __eq: function(integer , integer):(boolean)"
+    ///);
+    ///```
+    pub fn to_teal_metamethod(&self) -> Option<String> {
+        if !self.is_meta_method {
+            return None;
+        }
+        Some(format!(
+            "metamethod {}: {}",
+            self.name,
+            type_to_string(&self.into_type(), false)
+        ))
+    }
+    ///renders a meta method as a LuaLS `@operator` annotation, like `---@operator add(Vector): Vector`.
+    ///
+    ///Returns `None` if this function is not a meta method, or if it is one that LuaLS has no operator for, like `__index`
+    ///```
+    ///# use tealr::ExportedFunction;
+    ///let function = ExportedFunction::new::<(i64, i64), i64, _>("__add", true, None);
+    ///assert_eq!(function.to_lua_ls_operator().unwrap(), "---@operator add(integer): integer");
+    ///let function = ExportedFunction::new::<i64, String, _>("__tostring", true, None);
+    ///assert_eq!(function.to_lua_ls_operator(), None);
+    ///```
+    pub fn to_lua_ls_operator(&self) -> Option<String> {
+        if !self.is_meta_method {
+            return None;
+        }
+        let name = self.name.to_string();
+        let operator = name.strip_prefix("__")?;
+        let operands = match operator {
+            "unm" | "bnot" | "len" => "".to_owned(),
+            "call" => format!(
+                "({})",
+                self.params
+                    .iter()
+                    .skip(1)
+                    .map(|v| type_to_string(&v.ty, false))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            "add" | "sub" | "mul" | "div" | "mod" | "pow" | "idiv" | "band" | "bor" | "bxor"
+            | "shl" | "shr" | "concat" => format!(
+                "({})",
+                self.params
+                    .last()
+                    .map(|v| type_to_string(&v.ty, false))
+                    .unwrap_or_default()
+            ),
+            _ => return None,
+        };
+        let returns = if self.returns.is_empty() {
+            "nil".to_owned()
+        } else {
+            self.returns
+                .iter()
+                .map(|v| type_to_string(v, false))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        Some(format!(
+            "---@operator {}{}: {}",
+            operator, operands, returns
+        ))
+    }
+    ///renders the generics used by this function as a LuaLS `@generic` annotation, like `---@generic T: Comparable, U`.
+    ///
+    ///Returns `None` if this function doesn't use any generics
+    ///```
+    ///# use tealr::{ExportedFunction, KindOfType, ToTypename, Type};
+    ///let mut function = ExportedFunction::new::<String, String, _>("convert", false, None);
+    ///function.params[0].ty = Type::new_bounded_generic("T", String::to_typename());
+    ///function.returns[0] = Type::new_single("U", KindOfType::Generic);
+    ///assert_eq!(function.to_lua_ls_generics().unwrap(), "---@generic T: string, U");
+    ///let function = ExportedFunction::new::<String, String, _>("concat", false, None);
+    ///assert_eq!(function.to_lua_ls_generics(), None);
+    ///```
+    pub fn to_lua_ls_generics(&self) -> Option<String> {
+        let mut generics: Vec<_> = self
+            .get_generic_types()
+            .into_iter()
+            .filter_map(|v| match v {
+                Type::Single(x) => Some(x),
+                _ => None,
+            })
+            .collect();
+        if generics.is_empty() {
+            return None;
+        }
+        generics.sort_by(|a, b| a.name.0.cmp(&b.name.0));
+        let generics = generics
+            .iter()
+            .map(|v| match &v.bound {
+                Some(bound) => format!("{}: {}", v.name, type_to_string(bound, false)),
+                None => v.name.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        Some(format!("---@generic {}", generics))
+    }
+    ///checks if a meta method has a signature that lua can actually use, like `__eq` returning a boolean.
+    ///
+    ///Returns a message for every problem that got found, the list is empty if this function is not a meta method.
+    ///```
+    ///# use tealr::ExportedFunction;
+    ///let function = ExportedFunction::new::<(i64, i64), i64, _>("__eq", true, None);
+    ///assert_eq!(
+    ///    function.meta_method_warnings(),
+    ///    ["`__eq` should return a single boolean, but returns integer"]
+    ///);
+    ///```
+    pub fn meta_method_warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if !self.is_meta_method {
+            return warnings;
+        }
+        let name = self.name.to_string();
+        let returns = self
+            .returns
+            .iter()
+            .map(|v| type_to_string(v, false))
+            .collect::<Vec<_>>();
+        let is_variadic = self
+            .params
+            .iter()
+            .any(|v| matches!(v.ty, Type::Variadic(_)));
+        let expected_params = match name.as_str() {
+            "__add" | "__sub" | "__mul" | "__div" | "__mod" | "__pow" | "__idiv" | "__band"
+            | "__bor" | "__bxor" | "__shl" | "__shr" | "__concat" | "__eq" | "__lt" | "__le"
+            | "__index" => Some(2),
+            "__newindex" => Some(3),
+            "__unm" | "__bnot" | "__len" | "__tostring" | "__close" | "__pairs" | "__ipairs"
+            | "__iter" | "__call" => None,
+            //meta fields and meta methods that lua only uses internally
+            "__gc" | "__mode" | "__name" | "__metatable" | "__type" => None,
+            _ => {
+                warnings.push(format!("`{}` is not a meta method lua knows about", name));
+                None
+            }
+        };
+        if let Some(expected) = expected_params {
+            if !is_variadic && self.params.len() != expected {
+                warnings.push(format!(
+                    "`{}` gets called with {} values, but takes {}",
+                    name,
+                    expected,
+                    self.params.len()
+                ));
+            }
+        }
+        match name.as_str() {
+            "__eq" | "__lt" | "__le" if returns != ["boolean"] => warnings.push(format!(
+                "`{}` should return a single boolean, but returns {}",
+                name,
+                display_returns(&returns)
+            )),
+            "__tostring" if returns != ["string"] => warnings.push(format!(
+                "`{}` should return a single string, but returns {}",
+                name,
+                display_returns(&returns)
+            )),
+            "__newindex" | "__close" if !returns.is_empty() => warnings.push(format!(
+                "the values returned by `{}` are ignored, but it returns {}",
+                name,
+                display_returns(&returns)
+            )),
+            "__len" | "__unm" | "__bnot" | "__index" | "__concat" if returns.is_empty() => {
+                warnings.push(format!("`{}` should return a value", name))
+            }
+            _ => (),
+        }
+        warnings
+    }
     ///Get all the generics that this function uses.
     pub fn get_generics(&self) -> HashSet<&Name> {
         self.params
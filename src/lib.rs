@@ -5,8 +5,17 @@
 #[cfg(feature = "mlua")]
 pub mod mlu;
 
+///evaluate lua code and complete it using the types collected by a [TypeWalker]
+#[cfg(feature = "repl")]
+pub mod repl;
+
+mod deprecation;
+mod doc_gen;
 mod export_instance;
 mod exported_function;
+mod lua_literal;
+mod placement;
+mod stability;
 mod teal_multivalue;
 mod type_generator;
 mod type_representation;
@@ -14,23 +23,36 @@ mod type_walker;
 
 use std::{borrow::Cow, collections::HashSet};
 
-pub use exported_function::ExportedFunction;
+pub use deprecation::Deprecation;
+pub use doc_gen::{LuaExample, MarkdownPage};
+pub use exported_function::{ExportedFunction, FunctionDocs, ParamDocs};
+pub use lua_literal::ToLuaLiteral;
 #[cfg(feature = "mlua")]
 use mlu::TealDataMethods;
 #[cfg(feature = "mlua")]
 use mlua::UserDataRef;
+pub use placement::Placement;
 use serde::{Deserialize, Serialize};
+pub use stability::Stability;
 pub use teal_multivalue::{TealMultiValue, TealType};
 
 ///Implements [ToTypename](crate::ToTypename).
 ///
 ///`TypeName::get_type_name` will return the name of the rust type.
+///
+///Use `#[tealr(proxy_name = "...")]` to set the name of its `UserDataProxy`, see [ToTypename::PROXY_NAME].
 #[cfg(feature = "derive")]
 pub use tealr_derive::ToTypename;
 
-pub use type_generator::{EnumGenerator, Field, NameContainer, RecordGenerator, TypeGenerator};
+#[cfg(all(feature = "mlua", feature = "derive"))]
+pub use mlu::Union;
+
+pub use type_generator::{
+    EnumGenerator, EnumRepresentation, Field, NameContainer, RecordGenerator, TaggedUnionGenerator,
+    TaggedVariant, TypeGenerator,
+};
 pub use type_representation::{KindOfType, NamePart, TypeBody};
-pub use type_walker::{ExtraPage, GlobalInstance, TypeWalker};
+pub use type_walker::{ExportedModule, ExtraPage, GlobalInstance, TypeWalker};
 
 #[cfg(feature = "compile")]
 pub use tealr_derive::compile_inline_teal;
@@ -92,6 +114,28 @@ pub struct SingleType {
     pub kind: KindOfType,
     ///If a type has generics then they are stored here
     pub generics: Vec<Type>,
+    ///The type a generic type parameter is constrained to, if any.
+    ///
+    ///Rendered as `<T is Bound>` in teal and as `---@generic T: Bound` for LuaLS.
+    ///Teal only accepts a single type as bound, so unions like `<T is (A | B)>` are rejected by `tl`.
+    #[serde(default)]
+    pub bound: Option<Box<Type>>,
+}
+impl SingleType {
+    ///Creates a type that isn't bound to anything
+    pub fn new(name: Name, kind: KindOfType, generics: Vec<Type>) -> Self {
+        Self {
+            name,
+            kind,
+            generics,
+            bound: None,
+        }
+    }
+    ///Constrains this generic type parameter to the given type, see [bound](SingleType::bound)
+    pub fn with_bound(mut self, bound: Type) -> Self {
+        self.bound = Some(Box::new(bound));
+        self
+    }
 }
 #[derive(Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq)]
 #[cfg_attr(
@@ -214,6 +258,19 @@ fn add_methods_to_type<T: TealDataMethods<Type>>(methods: &mut T) {
     })
 }
 
+#[cfg(feature = "mlua")]
+impl mlua::FromLua for Box<Type> {
+    fn from_lua(value: mlua::Value, lua: &mlua::Lua) -> mlua::Result<Self> {
+        Type::from_lua(value, lua).map(Box::new)
+    }
+}
+#[cfg(feature = "mlua")]
+impl mlua::IntoLua for Box<Type> {
+    fn into_lua(self, lua: &mlua::Lua) -> mlua::Result<mlua::Value> {
+        (*self).into_lua(lua)
+    }
+}
+
 impl From<Box<Type>> for Type {
     fn from(value: Box<Type>) -> Self {
         *value
@@ -230,11 +287,20 @@ impl Type {
         kind: KindOfType,
         generics: Vec<Type>,
     ) -> Self {
-        Self::Single(SingleType {
-            name: name.into(),
-            kind,
-            generics,
-        })
+        Self::Single(SingleType::new(name.into(), kind, generics))
+    }
+    ///Creates a generic type parameter that is constrained to the given type
+    ///```
+    ///# use tealr::{type_to_string, Type, ToTypename};
+    ///let generic = Type::new_bounded_generic("T", String::to_typename());
+    ///let function = Type::Function(tealr::FunctionRepresentation {
+    ///    params: vec![tealr::FunctionParam { param_name: None, ty: generic.clone() }],
+    ///    returns: vec![generic],
+    ///});
+    ///assert_eq!(type_to_string(&function, false), "function<T is string>(T):(T)");
+    ///```
+    pub fn new_bounded_generic(name: impl AsRef<str>, bound: Type) -> Self {
+        Self::Single(SingleType::new(name.into(), KindOfType::Generic, vec![]).with_bound(bound))
     }
 
     ///returns Some(X) if Self is `Single`. Otherwise None
@@ -248,6 +314,10 @@ impl Type {
 }
 ///This trait turns a A into a type representation for Lua/Teal
 pub trait ToTypename {
+    ///The name of the `UserDataProxy` of this type, `Class{Name}` if it isn't set.
+    ///
+    ///Can be set using `#[tealr(proxy_name = "...")]` when deriving `ToTypename`.
+    const PROXY_NAME: Option<&'static str> = None;
     ///generates the type representation
     fn to_typename() -> Type;
     ///generates the type representation when used as a parameter
@@ -382,17 +452,25 @@ pub fn type_to_teal_parts(a: &Type, is_callback: bool) -> Cow<'static, [NamePart
                     .iter()
                     .map(|v| &v.ty)
                     .chain(returns.iter())
-                    .flat_map(get_generics)
+                    .flat_map(get_generic_types)
                     .collect();
                 let generic_amount = generics.len();
                 if (!is_callback) && generic_amount > 0 {
                     parts.push(NamePart::Symbol("<".into()));
-                    for generic in generics {
+                    for generic in generics.iter().filter_map(Type::single) {
                         parts.push(NamePart::Type(TealType {
-                            name: generic.0.clone(),
+                            name: generic.name.0.clone(),
                             type_kind: KindOfType::Generic,
                             generics: None,
                         }));
+                        if let Some(bound) = &generic.bound {
+                            parts.push(NamePart::symbol(" is "));
+                            parts.extend(
+                                type_to_teal_parts_helper(bound, true, AsPartOf::Other)
+                                    .iter()
+                                    .cloned(),
+                            );
+                        }
                         parts.push(NamePart::symbol(","));
                     }
                     parts.pop();
@@ -0,0 +1,121 @@
+use std::borrow::Cow;
+
+///Turns a value into the lua code that creates that same value.
+///
+///Used to document the value of constants.
+///```
+///# use tealr::ToLuaLiteral;
+///assert_eq!(64.to_lua_literal(), "64");
+///assert_eq!("a \"quoted\" string".to_lua_literal(), r#""a \"quoted\" string""#);
+///assert_eq!(Some(true).to_lua_literal(), "true");
+///assert_eq!(None::<bool>.to_lua_literal(), "nil");
+///assert_eq!(i64::MIN.to_lua_literal(), "math.mininteger");
+///assert_eq!(u64::MAX.to_lua_literal(), "1.8446744073709552e19");
+///```
+pub trait ToLuaLiteral {
+    ///creates the lua code representing this value
+    fn to_lua_literal(&self) -> String;
+}
+
+macro_rules! impl_to_lua_literal_display {
+    ($($current_type:ty),*) => {
+        $(
+            impl ToLuaLiteral for $current_type {
+                fn to_lua_literal(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+impl_to_lua_literal_display!(bool, i8, u8, i16, u16, i32, u32);
+
+//lua integers are 64 bits, so bigger values can only be written as floats.
+//`i64::MIN` can't be written as a literal either, as lua reads it as the negation of a too big number
+macro_rules! impl_to_lua_literal_integer {
+    ($($current_type:ty),*) => {
+        $(
+            impl ToLuaLiteral for $current_type {
+                fn to_lua_literal(&self) -> String {
+                    match i64::try_from(*self) {
+                        Ok(i64::MIN) => "math.mininteger".to_string(),
+                        Ok(x) => x.to_string(),
+                        Err(_) => (*self as f64).to_lua_literal(),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_to_lua_literal_integer!(i64, u64, i128, u128, isize, usize);
+
+macro_rules! impl_to_lua_literal_float {
+    ($($current_type:ty),*) => {
+        $(
+            impl ToLuaLiteral for $current_type {
+                fn to_lua_literal(&self) -> String {
+                    if self.is_nan() {
+                        "0/0".to_string()
+                    } else if self.is_infinite() && self.is_sign_positive() {
+                        "math.huge".to_string()
+                    } else if self.is_infinite() {
+                        "-math.huge".to_string()
+                    } else {
+                        format!("{:?}", self)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_to_lua_literal_float!(f32, f64);
+
+impl ToLuaLiteral for str {
+    fn to_lua_literal(&self) -> String {
+        let mut literal = String::with_capacity(self.len() + 2);
+        literal.push('"');
+        for char in self.chars() {
+            match char {
+                '"' => literal.push_str("\\\""),
+                '\\' => literal.push_str("\\\\"),
+                '\n' => literal.push_str("\\n"),
+                '\r' => literal.push_str("\\r"),
+                '\t' => literal.push_str("\\t"),
+                x if x.is_ascii_control() => literal.push_str(&format!("\\{:03}", x as u8)),
+                x => literal.push(x),
+            }
+        }
+        literal.push('"');
+        literal
+    }
+}
+
+impl ToLuaLiteral for String {
+    fn to_lua_literal(&self) -> String {
+        self.as_str().to_lua_literal()
+    }
+}
+
+impl ToLuaLiteral for Cow<'_, str> {
+    fn to_lua_literal(&self) -> String {
+        self.as_ref().to_lua_literal()
+    }
+}
+
+impl<T: ToLuaLiteral + ?Sized> ToLuaLiteral for &T {
+    fn to_lua_literal(&self) -> String {
+        (*self).to_lua_literal()
+    }
+}
+
+impl<T: ToLuaLiteral> ToLuaLiteral for Option<T> {
+    fn to_lua_literal(&self) -> String {
+        match self {
+            Some(x) => x.to_lua_literal(),
+            None => "nil".to_string(),
+        }
+    }
+}
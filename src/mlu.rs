@@ -1,7 +1,11 @@
+mod deprecation_hook;
+mod doc_examples;
 ///this module holds some pre made types that can be used to create generics.
 pub mod generics;
+pub(crate) mod help;
 mod named_parameters;
 mod picker_macro;
+mod sandbox;
 pub(crate) mod teal_data;
 mod teal_data_fields;
 pub(crate) mod teal_data_methods;
@@ -13,9 +17,14 @@ mod variadics;
 use std::borrow::Cow;
 
 pub use self::{
+    deprecation_hook::set_deprecation_hook,
+    doc_examples::{run_lua_examples, ExampleFailure, ExampleFailures},
     picker_macro::FromLuaExact,
+    sandbox::{SandboxBuilder, SAFE_STD_LIB},
     teal_data::TealData,
-    teal_data_methods::{set_global_env, ExportInstances, InstanceCollector, TealDataMethods},
+    teal_data_methods::{
+        set_global_env, set_module_env, ExportInstances, InstanceCollector, TealDataMethods,
+    },
     typed_function::TypedFunction,
     user_data_proxy::UserDataProxy,
     user_data_wrapper::UserDataWrapper,
@@ -95,6 +104,58 @@ impl<T> MaybeSend for T {}
 #[cfg(feature = "derive")]
 pub use tealr_derive::MluaFromToLua as FromToLua;
 
+///Turns an enum where every variant holds a single value into a union of the types of those values.
+///
+///Unlike [create_union_mlua](crate::create_union_mlua) the variants can hold any type, including generic ones.
+///Converting from lua tries [FromLuaExact](crate::mlu::FromLuaExact) for every variant, in order, so put the most specific types first.
+///If none of them match, the error lists every alternative that got tried.
+///It is documented as a union of every type, so `(string | {string})` for the example below.
+///```
+///# use tealr::{mlu::mlua::Lua, type_to_string, ToTypename, Union};
+///#[derive(Union)]
+///enum Arg {
+///    Text(String),
+///    List(Vec<String>),
+///}
+///assert_eq!(type_to_string(&Arg::to_typename(), false), "(string | {string})");
+///let lua = Lua::new();
+///let arg: Arg = lua.load("return {'a', 'b'}").eval().unwrap();
+///assert!(matches!(arg, Arg::List(x) if x.len() == 2));
+///```
+#[cfg(feature = "derive")]
+pub use tealr_derive::MluaUnion as Union;
+
+///Implements [FromLuaExact](trait@crate::mlu::FromLuaExact), so the type can be used in a [Union].
+///
+///Structs are read from a table with exactly the same shape as the one [FromToLua] produces.
+///Every field is converted with [FromLuaExact](trait@crate::mlu::FromLuaExact) and tables with keys that are not a field are rejected.
+///`#[tealr(remote = OtherType)]` on a field is honored the same way as [FromToLua] does.
+///
+///Enums without fields only accept a string naming one of the variants, other enums only accept userdata of the same type.
+///Both then use the [FromLua](mlua::FromLua) implementation of the type.
+///Enums stored as plain values, like with `#[tealr(tagged = "...")]`, are read the same strict way as structs, using the fields of the variant the tag names.
+///See [FromToLua] for the ways enums can be stored.
+///Structs marked with `#[tealr(userdata)]` only accept userdata of the same type and get cloned out of it.
+///```
+///# use tealr::{mlu::{mlua::Lua, FromLuaExact, FromToLua}, ToTypename, Union};
+///#[derive(Clone, FromToLua, ToTypename, FromLuaExact)]
+///struct Point {
+///    x: i64,
+///    y: i64,
+///}
+///#[derive(Union)]
+///enum Shape {
+///    Point(Point),
+///    Name(String),
+///}
+///let lua = Lua::new();
+///let shape: Shape = lua.load("return {x = 1, y = 2}").eval().unwrap();
+///assert!(matches!(shape, Shape::Point(Point { x: 1, y: 2 })));
+///assert!(lua.load("return {x = 1, y = 2, z = 3}").eval::<Shape>().is_err());
+///```
+#[cfg(feature = "derive")]
+pub use tealr_derive::MluaFromLuaExact as FromLuaExact;
+
 ///Implement both [mlua::UserData](mlua::UserData) and [TypeName](crate::ToTypename).
 ///
 ///Look at [tealr_derive::MluaUserData](tealr_derive::MluaUserData) and [tealr_derive::TypeName](tealr_derive::TypeName)
@@ -0,0 +1,73 @@
+use std::{cell::RefCell, collections::HashSet};
+
+use mlua::{Function, Lua, MultiValue, Result, Value};
+
+use super::MaybeSend;
+use crate::Deprecation;
+
+struct DeprecationHook {
+    hook: Function,
+    warned: RefCell<HashSet<String>>,
+}
+
+///Sets the function that gets called the first time a deprecated method, function, field or global function is used from lua.
+///
+///The hook gets a message describing what got called and why it is deprecated.
+///Every deprecated member only triggers the hook once per lua state. Without a hook, nothing happens.
+///```
+///# use tealr::mlu::{mlua::Lua, set_deprecation_hook};
+///let lua = Lua::new();
+///set_deprecation_hook(&lua, |lua, message| {
+///    lua.load(format!("print({:?})", message)).exec()
+///})?;
+///# Ok::<(), tealr::mlu::mlua::Error>(())
+///```
+pub fn set_deprecation_hook<F>(lua: &Lua, hook: F) -> Result<()>
+where
+    F: Fn(&Lua, &str) -> Result<()> + MaybeSend + 'static,
+{
+    //stored as a lua function so it can be cloned out of the app data before it runs
+    let hook = lua.create_function(move |lua, message: String| hook(lua, &message))?;
+    lua.set_app_data(DeprecationHook {
+        hook,
+        warned: Default::default(),
+    });
+    Ok(())
+}
+
+pub(crate) fn warn_deprecated(lua: &Lua, name: &str, deprecation: &Deprecation) -> Result<()> {
+    let hook = match lua.app_data_ref::<DeprecationHook>() {
+        Some(hook) if hook.warned.borrow_mut().insert(name.to_owned()) => hook.hook.clone(),
+        _ => return Ok(()),
+    };
+    hook.call(format!("`{}` is {}", name, deprecation))
+}
+
+///the deprecation of the next global instance that gets added to a table, as the table itself can't store it
+struct NextGlobalDeprecation(Deprecation);
+
+pub(crate) fn set_next_global_deprecation(lua: &Lua, deprecation: Deprecation) {
+    lua.set_app_data(NextGlobalDeprecation(deprecation));
+}
+
+pub(crate) fn take_next_global_deprecation(lua: &Lua) -> Option<Deprecation> {
+    lua.remove_app_data::<NextGlobalDeprecation>().map(|v| v.0)
+}
+
+///wraps a deprecated global instance, so calling it warns if it is a function
+pub(crate) fn deprecated_global(
+    lua: &Lua,
+    name: String,
+    deprecation: Deprecation,
+    value: Value,
+) -> Result<Value> {
+    let function = match value {
+        Value::Function(x) => x,
+        x => return Ok(x),
+    };
+    lua.create_function(move |lua, args: MultiValue| {
+        warn_deprecated(lua, &name, &deprecation)?;
+        function.call::<MultiValue>(args)
+    })
+    .map(Value::Function)
+}
@@ -0,0 +1,91 @@
+use std::fmt::{Debug, Display};
+
+use mlua::Lua;
+
+use crate::{LuaExample, TypeWalker};
+
+///An example from the documentation that failed to run
+pub struct ExampleFailure {
+    ///the example that failed
+    pub example: LuaExample,
+    ///the error it failed with
+    pub error: mlua::Error,
+}
+
+impl Display for ExampleFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "example of `{}` failed: {}\n{}",
+            self.example.location(),
+            self.error,
+            self.example.code
+        )
+    }
+}
+
+///Every example that failed when running them with [run_lua_examples]
+pub struct ExampleFailures {
+    ///the examples that failed, in the order they got run
+    pub failures: Vec<ExampleFailure>,
+    ///the amount of examples that got run
+    pub total: usize,
+}
+
+impl Display for ExampleFailures {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} of {} examples failed",
+            self.failures.len(),
+            self.total
+        )?;
+        for failure in &self.failures {
+            writeln!(f, "\n{}", failure)?;
+        }
+        Ok(())
+    }
+}
+
+//shows the same as Display, so `.unwrap()` in a test gives a readable report
+impl Debug for ExampleFailures {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for ExampleFailures {}
+
+///Runs every lua example found by [TypeWalker::lua_examples] in the given lua state.
+///
+///The lua state should already contain the instances the examples use, for example by using [set_global_env](crate::mlu::set_global_env).
+///Every example is run, even if an earlier one failed. On success, the amount of examples that got run is returned.
+///```
+///# use tealr::{mlu::{mlua::Lua, run_lua_examples}, RecordGenerator, ToTypename, TypeWalker};
+///#[derive(ToTypename)]
+///struct Example {}
+///let mut record = RecordGenerator::new::<Example>(false);
+///record.type_doc = "```lua\nassert(1 + 1 == 2)\n```".to_owned();
+///let mut walker = TypeWalker::new();
+///walker.given_types.push(record.into());
+///assert_eq!(run_lua_examples(&Lua::new(), &walker).unwrap(), 1);
+///```
+pub fn run_lua_examples(lua: &Lua, walker: &TypeWalker) -> Result<usize, ExampleFailures> {
+    let examples = walker.lua_examples();
+    let total = examples.len();
+    let failures = examples
+        .into_iter()
+        .filter_map(|example| {
+            lua.load(&example.code)
+                .set_name(format!("={}", example.location()))
+                .exec()
+                .err()
+                .map(|error| ExampleFailure { example, error })
+        })
+        .collect::<Vec<_>>();
+    if failures.is_empty() {
+        Ok(total)
+    } else {
+        Err(ExampleFailures { failures, total })
+    }
+}
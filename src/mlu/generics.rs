@@ -12,17 +12,65 @@
 /// let x = tealr::mlu::TypedFunction::<YourPublicType, YourPublicType>::from_rust(|_, x| Ok(x), &lua);
 ///
 ///```
+///
+///A generic can also be constrained to a type by giving it a bound that implements [FromLuaExact](crate::mlu::FromLuaExact).
+///It is exposed to teal as `<T is Bound>`, and converting a lua value into it fails if the value can't be read as the bound.
+///Teal only accepts a single type as bound, so the bound can't be a union made with [create_union_mlua](crate::create_union_mlua).
+///```
+/// let lua = tealr::mlu::mlua::Lua::new();
+/// tealr::create_generic_mlua!(Text: String);
+/// //x will be exposed to lua as `function<Text is string>(Text): Text`
+/// let x = tealr::mlu::TypedFunction::<Text, Text>::from_rust(|_, x| Ok(x), &lua).unwrap();
+/// lua.globals().set("x", x).unwrap();
+/// assert!(lua.load("return x('some text')").exec().is_ok());
+/// assert!(lua.load("return x(1)").exec().is_err());
+///```
 #[macro_export]
 macro_rules! create_generic_mlua {
     ($visibility:vis $type_name:ident) => {
-        #[derive(Clone,Debug)]
-        #[allow(missing_docs)]
-        $visibility struct $type_name ($crate::mlu::mlua::Value);
+        $crate::create_generic_mlua!(@shared $visibility $type_name);
         impl $crate::mlu::mlua::FromLua for $type_name {
             fn from_lua(value: $crate::mlu::mlua::Value, _: &$crate::mlu::mlua::Lua) -> ::std::result::Result<Self, $crate::mlu::mlua::Error> {
                 Ok(value.into())
             }
         }
+        impl $crate::ToTypename for $type_name {
+            fn to_typename() -> $crate::Type {
+                $crate::Type::new_single(stringify!($type_name), $crate::KindOfType::Generic)
+            }
+        }
+    };
+    ($visibility:vis $type_name:ident : $bound:ty) => {
+        $crate::create_generic_mlua!(@shared $visibility $type_name);
+        impl $crate::mlu::mlua::FromLua for $type_name {
+            fn from_lua(value: $crate::mlu::mlua::Value, lua: &$crate::mlu::mlua::Lua) -> ::std::result::Result<Self, $crate::mlu::mlua::Error> {
+                match <$bound as $crate::mlu::FromLuaExact>::from_lua_exact(value.clone(), lua) {
+                    Ok(_) => Ok(value.into()),
+                    Err(error) => Err($crate::mlu::mlua::Error::FromLuaConversionError {
+                        from: value.type_name(),
+                        to: stringify!($type_name).to_owned(),
+                        message: Some(format!(
+                            "value does not satisfy the bound `{}`: {}",
+                            $crate::type_to_string(&<$bound as $crate::ToTypename>::to_typename(), false),
+                            error
+                        )),
+                    }),
+                }
+            }
+        }
+        impl $crate::ToTypename for $type_name {
+            fn to_typename() -> $crate::Type {
+                $crate::Type::new_bounded_generic(
+                    stringify!($type_name),
+                    <$bound as $crate::ToTypename>::to_typename(),
+                )
+            }
+        }
+    };
+    (@shared $visibility:vis $type_name:ident) => {
+        #[derive(Clone,Debug)]
+        #[allow(missing_docs)]
+        $visibility struct $type_name ($crate::mlu::mlua::Value);
         impl $crate::mlu::mlua::IntoLua for $type_name {
             fn into_lua(self, _: &$crate::mlu::mlua::Lua) -> ::std::result::Result<$crate::mlu::mlua::Value, $crate::mlu::mlua::Error> {
                 Ok(self.into())
@@ -53,11 +101,6 @@ macro_rules! create_generic_mlua {
                 <$crate::mlu::mlua::Value as ::core::cmp::PartialEq<$crate::mlu::mlua::Value>>::eq(&self.0, &other.0)
             }
         }
-        impl $crate::ToTypename for $type_name {
-            fn to_typename() -> $crate::Type {
-                $crate::Type::new_single(stringify!($type_name), $crate::KindOfType::Generic)
-            }
-        }
         impl $type_name {
             ///compares the 2 values, taking the __eq meta method into account if it is set.
             pub fn equals(&self, other: &$type_name) -> $crate::mlu::mlua::Result<bool> {
@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+
+use mlua::{Lua, ObjectLike, Result, Value};
+
+use crate::{type_to_string, Deprecation, ExportedFunction, Field, RecordGenerator, Stability};
+
+///What kind of member a [HelpEntry] documents
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum HelpKind {
+    Field,
+    StaticField,
+    MetaField,
+    Method,
+    Function,
+    MetaMethod,
+    MetaFunction,
+    Constructor,
+}
+
+impl HelpKind {
+    ///the name shown in the help pages and the `kind` of `help_entries`
+    fn as_str(self) -> &'static str {
+        match self {
+            HelpKind::Field => "field",
+            HelpKind::StaticField => "static field",
+            HelpKind::MetaField => "meta field",
+            HelpKind::Method => "method",
+            HelpKind::Function => "function",
+            HelpKind::MetaMethod => "meta method",
+            HelpKind::MetaFunction => "meta function",
+            HelpKind::Constructor => "constructor",
+        }
+    }
+}
+
+///A single member of a type, as shown by the generated `help` functions
+#[derive(Clone, Debug)]
+pub(crate) struct HelpEntry {
+    pub(crate) name: String,
+    pub(crate) signature: String,
+    pub(crate) doc: String,
+    pub(crate) kind: HelpKind,
+}
+
+impl HelpEntry {
+    fn page(&self) -> String {
+        format!("{}\n\ndocs:\n{}", self.signature, self.doc)
+    }
+    fn to_table(&self) -> HashMap<String, String> {
+        [
+            ("name", self.name.as_str()),
+            ("signature", &self.signature),
+            ("doc", &self.doc),
+            ("kind", self.kind.as_str()),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        .collect()
+    }
+    ///how well the entry matches the query, lower is better. `None` if it doesn't match at all
+    fn score(&self, query: &str) -> Option<u8> {
+        let name = self.name.to_lowercase();
+        if name == query {
+            Some(0)
+        } else if name.starts_with(query) {
+            Some(1)
+        } else if name.contains(query) {
+            Some(2)
+        } else if is_subsequence(query, &name) {
+            Some(3)
+        } else if self.doc.to_lowercase().contains(query) {
+            Some(4)
+        } else {
+            None
+        }
+    }
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|c| haystack.any(|v| v == c))
+}
+
+fn metadata_docs(
+    deprecated: Option<&Deprecation>,
+    since: Option<&str>,
+    stability: Stability,
+) -> String {
+    let mut docs = String::new();
+    if let Some(deprecation) = deprecated {
+        docs.push_str(&format!("{}\n", deprecation));
+    }
+    if let Some(since) = since {
+        docs.push_str(&format!("since: {}\n", since));
+    }
+    if stability != Stability::Stable {
+        docs.push_str(&format!("stability: {}\n", stability));
+    }
+    docs
+}
+
+///The documentation of a type, in the form used by the generated `help` functions
+#[derive(Clone, Debug)]
+pub(crate) struct HelpPages {
+    type_doc: String,
+    entries: Vec<HelpEntry>,
+}
+
+impl HelpPages {
+    pub(crate) fn from_record(record: &RecordGenerator) -> Self {
+        let mut pages = Self {
+            type_doc: record.type_doc.clone(),
+            entries: Vec::new(),
+        };
+        for (kind, fields) in [
+            (HelpKind::Field, &record.fields),
+            (HelpKind::StaticField, &record.field_functions),
+            (HelpKind::MetaField, &record.static_fields),
+        ] {
+            for field in fields {
+                pages.add_field(record, kind, field);
+            }
+        }
+        for (kind, functions) in [
+            (HelpKind::Method, &record.methods),
+            (HelpKind::Method, &record.mut_methods),
+            (HelpKind::Function, &record.functions),
+            (HelpKind::Function, &record.mut_functions),
+            (HelpKind::MetaMethod, &record.meta_method),
+            (HelpKind::MetaMethod, &record.meta_method_mut),
+            (HelpKind::MetaFunction, &record.meta_function),
+            (HelpKind::MetaFunction, &record.meta_function_mut),
+        ] {
+            for function in functions {
+                if record.is_constructor(function) {
+                    //the constructor is only listed once, as `new`
+                    if !function.is_meta_method {
+                        pages.add_function(record, HelpKind::Constructor, function);
+                    }
+                } else {
+                    pages.add_function(record, kind, function);
+                }
+            }
+        }
+        pages
+    }
+    fn add_field(&mut self, record: &RecordGenerator, kind: HelpKind, field: &Field) {
+        let name = field.name.to_string();
+        //fields with both a getter and a setter get added twice
+        if self
+            .entries
+            .iter()
+            .any(|v| v.name == name && v.kind == kind)
+        {
+            return;
+        }
+        let mut signature = type_to_string(&field.ty, false);
+        if let Some(value) = &field.value {
+            signature.push_str(" = ");
+            signature.push_str(value);
+        }
+        let doc = metadata_docs(
+            field.deprecated.as_ref(),
+            field.since.as_deref(),
+            field.stability,
+        ) + record
+            .documentation
+            .get(&field.name)
+            .map(String::as_str)
+            .unwrap_or_default();
+        self.entries.push(HelpEntry {
+            name,
+            signature,
+            doc,
+            kind,
+        });
+    }
+    fn add_function(
+        &mut self,
+        record: &RecordGenerator,
+        kind: HelpKind,
+        function: &ExportedFunction,
+    ) {
+        let doc = metadata_docs(
+            function.deprecated.as_ref(),
+            function.since.as_deref(),
+            function.stability,
+        ) + record
+            .documentation
+            .get(&function.name)
+            .map(String::as_str)
+            .unwrap_or_default()
+            + &function.docs.to_help_text();
+        self.entries.push(HelpEntry {
+            name: function.name.to_string(),
+            signature: type_to_string(&function.into_type(), false),
+            doc,
+            kind,
+        });
+    }
+    ///every documented member, in the order they got added
+    #[cfg(feature = "repl")]
+    pub(crate) fn iter(&self) -> std::slice::Iter<'_, HelpEntry> {
+        self.entries.iter()
+    }
+    ///every entry matching the query, best matches first
+    fn search(&self, query: &str) -> Vec<&HelpEntry> {
+        let query = query.to_lowercase();
+        let mut found = self
+            .entries
+            .iter()
+            .filter_map(|v| v.score(&query).map(|score| (score, v)))
+            .collect::<Vec<_>>();
+        found.sort_by_key(|(score, _)| *score);
+        found.into_iter().map(|(_, v)| v).collect()
+    }
+    ///the text returned by `help(query)`
+    pub(crate) fn page(&self, query: Option<&str>) -> String {
+        let query = match query {
+            Some(x) => x,
+            None => {
+                let mut page = self.type_doc.clone() + "\n" + "Available pages:\n";
+                let mut listed = Vec::new();
+                for entry in &self.entries {
+                    if !listed.contains(&&entry.name) {
+                        listed.push(&entry.name);
+                        page.push_str(&entry.name);
+                        page.push('\n');
+                    }
+                }
+                return page;
+            }
+        };
+        let exact = self
+            .entries
+            .iter()
+            .filter(|v| v.name == query)
+            .map(HelpEntry::page)
+            .collect::<Vec<_>>();
+        if !exact.is_empty() {
+            return exact.join("\n\n");
+        }
+        let found = self.search(query);
+        if found.is_empty() {
+            return "The given key is not found. Use `.help()` to list available pages.".to_owned();
+        }
+        let mut page = format!("No page named `{}` was found. Did you mean:\n", query);
+        for entry in found {
+            page.push_str(&format!(
+                "  {} ({}): {}\n",
+                entry.name,
+                entry.kind.as_str(),
+                entry.signature
+            ));
+        }
+        page
+    }
+    ///the tables returned by `help_entries(query)`
+    pub(crate) fn entries(&self, query: Option<&str>) -> Vec<HashMap<String, String>> {
+        match query {
+            Some(query) => self
+                .search(query)
+                .into_iter()
+                .map(HelpEntry::to_table)
+                .collect(),
+            None => self.entries.iter().map(HelpEntry::to_table).collect(),
+        }
+    }
+}
+
+///shows the documentation of any value that has a generated `help` function, or of the global with the given (dotted) name
+pub(crate) fn global_help(
+    lua: &Lua,
+    (value, key): (Value, Option<mlua::String>),
+) -> Result<String> {
+    let key = key.map(|v| v.to_string_lossy());
+    let (value, key) = match value {
+        Value::String(name) => {
+            let name = name.to_string_lossy();
+            let parts = name.split('.').collect::<Vec<_>>();
+            let mut current = Value::Table(lua.globals());
+            let mut used = 0;
+            for part in &parts {
+                current = match &current {
+                    Value::Table(x) => x.get(*part)?,
+                    _ => break,
+                };
+                used += 1;
+            }
+            //whatever is left of the path is the member to show the help page of
+            let key = if used < parts.len() {
+                Some(parts[used..].join("."))
+            } else {
+                key
+            };
+            (current, key)
+        }
+        x => (x, key),
+    };
+    let help = match &value {
+        Value::Table(x) => x.get::<Value>("help")?,
+        Value::UserData(x) => x.get::<Value>("help").unwrap_or(Value::Nil),
+        _ => Value::Nil,
+    };
+    match help {
+        Value::Function(help) => help.call(key),
+        _ => Ok(format!(
+            "No documentation available for this {}",
+            value.type_name()
+        )),
+    }
+}
@@ -1,13 +1,18 @@
-use mlua::{Error, Function, Lua, Table, Value};
+use mlua::{Error, FromLua, Function, Lua, Table, UserDataRef, UserDataRefMut, Value};
 use std::ops::Deref;
 use std::{
     collections::{BTreeMap, HashMap},
     ffi::{CStr, CString},
     num::TryFromIntError,
+    path::PathBuf,
 };
 
+use crate::{mlu::TypedFunction, TealMultiValue};
+
 /// similar to [mlua::FromLua](mlua::FromLua). However,
 /// however going through this trait you promise that the conversion to a rust value prefers failing over converting/casting
+///
+/// It can be derived for structs and enums with [FromLuaExact](derive@crate::mlu::FromLuaExact).
 pub trait FromLuaExact: Sized {
     ///Does the conversion, without any type conversion/casting
     fn from_lua_exact(value: Value, lua: &Lua) -> mlua::Result<Self>;
@@ -18,6 +23,8 @@ pub trait FromLuaExact: Sized {
 ///It gets translated to a [union](https://github.com/teal-language/tl/blob/master/docs/tutorial.md#union-types) type in `teal`
 ///and an enum on Rust.
 ///
+///Only types that are a single identifier can be used. Use [Union](crate::mlu::Union) for other types, like `Vec<String>`.
+///
 ///# Warning:
 ///`teal` has a few restrictions on what it finds a valid union types. `tealr` does ***NOT*** check if the types you put in are a valid combination
 ///
@@ -145,6 +152,85 @@ macro_rules! impl_from_exact {
     };
 }
 
+impl FromLuaExact for bstr::BString {
+    fn from_lua_exact(value: Value, lua: &Lua) -> mlua::Result<Self> {
+        let as_str = mlua::String::from_lua_exact(value, lua)?;
+        Ok(as_str.as_bytes().to_vec().into())
+    }
+}
+
+impl FromLuaExact for PathBuf {
+    fn from_lua_exact(value: Value, lua: &Lua) -> mlua::Result<Self> {
+        String::from_lua_exact(value, lua)
+            .map(Into::into)
+            .map_err(|x| match x {
+                Error::FromLuaConversionError {
+                    from,
+                    to: _,
+                    message,
+                } => Error::FromLuaConversionError {
+                    from,
+                    to: "PathBuf".to_string(),
+                    message,
+                },
+                x => x,
+            })
+    }
+}
+
+impl FromLuaExact for Value {
+    fn from_lua_exact(value: Value, _: &Lua) -> mlua::Result<Self> {
+        Ok(value)
+    }
+}
+
+impl<Params: TealMultiValue, Response: TealMultiValue> FromLuaExact
+    for TypedFunction<Params, Response>
+{
+    fn from_lua_exact(value: Value, lua: &Lua) -> mlua::Result<Self> {
+        match value {
+            Value::Function(_) => Self::from_lua(value, lua),
+            x => Err(Error::FromLuaConversionError {
+                from: x.type_name(),
+                to: "TypedFunction".to_string(),
+                message: None,
+            }),
+        }
+    }
+}
+
+impl<T: 'static> FromLuaExact for UserDataRef<T> {
+    fn from_lua_exact(value: Value, lua: &Lua) -> mlua::Result<Self> {
+        match value {
+            Value::UserData(ref x) if x.is::<T>() => Self::from_lua(value, lua),
+            x => Err(Error::FromLuaConversionError {
+                from: x.type_name(),
+                to: "UserDataRef".to_string(),
+                message: Some(format!(
+                    "expected userdata of type {}",
+                    std::any::type_name::<T>()
+                )),
+            }),
+        }
+    }
+}
+
+impl<T: 'static> FromLuaExact for UserDataRefMut<T> {
+    fn from_lua_exact(value: Value, lua: &Lua) -> mlua::Result<Self> {
+        match value {
+            Value::UserData(ref x) if x.is::<T>() => Self::from_lua(value, lua),
+            x => Err(Error::FromLuaConversionError {
+                from: x.type_name(),
+                to: "UserDataRefMut".to_string(),
+                message: Some(format!(
+                    "expected userdata of type {}",
+                    std::any::type_name::<T>()
+                )),
+            }),
+        }
+    }
+}
+
 impl<T: FromLuaExact> FromLuaExact for Option<T> {
     fn from_lua_exact(value: Value, lua: &Lua) -> mlua::Result<Self> {
         match value {
@@ -252,6 +338,57 @@ impl<T: FromLuaExact, const N: usize> FromLuaExact for [T; N] {
     }
 }
 
+macro_rules! impl_from_exact_tuple {
+    ($($name:ident)+) => {
+        impl<$($name: FromLuaExact),+> FromLuaExact for ($($name,)+) {
+            fn from_lua_exact(value: Value, lua: &Lua) -> mlua::Result<Self> {
+                const LEN: usize = [$(stringify!($name)),+].len();
+                let table = match value {
+                    Value::Table(x) => x,
+                    x => {
+                        return Err(Error::FromLuaConversionError {
+                            from: x.type_name(),
+                            to: "Tuple".to_string(),
+                            message: Some(format!("Expected a sequence of exactly length {}", LEN)),
+                        })
+                    }
+                };
+                let len = table.raw_len();
+                if len != LEN || table.pairs::<Value, Value>().count() != LEN {
+                    return Err(Error::FromLuaConversionError {
+                        from: "Table",
+                        to: "Tuple".to_string(),
+                        message: Some(format!("Expected a sequence of exactly length {}, got {}", LEN, len)),
+                    });
+                }
+                let mut values = table.sequence_values::<Value>();
+                Ok(($(
+                    $name::from_lua_exact(
+                        match values.next() {
+                            Some(x) => x?,
+                            None => unreachable!(),
+                        },
+                        lua,
+                    )?,
+                )+))
+            }
+        }
+    };
+}
+
+impl_from_exact_tuple!(A);
+impl_from_exact_tuple!(A B);
+impl_from_exact_tuple!(A B C);
+impl_from_exact_tuple!(A B C D);
+impl_from_exact_tuple!(A B C D E);
+impl_from_exact_tuple!(A B C D E F);
+impl_from_exact_tuple!(A B C D E F G);
+impl_from_exact_tuple!(A B C D E F G H);
+impl_from_exact_tuple!(A B C D E F G H I);
+impl_from_exact_tuple!(A B C D E F G H I J);
+impl_from_exact_tuple!(A B C D E F G H I J K);
+impl_from_exact_tuple!(A B C D E F G H I J K L);
+
 impl_from_exact_non_failing!(bool, mlua::Value::Boolean(x), x);
 
 impl_from_exact_non_failing!(Function, mlua::Value::Function(x), x);
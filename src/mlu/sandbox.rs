@@ -0,0 +1,183 @@
+use std::borrow::Cow;
+
+use mlua::{Lua, Result, Table, Value};
+
+use super::{teal_data_methods::set_in_namespace, ExportInstances};
+use crate::TypeWalker;
+
+///The parts of the lua standard library that [SandboxBuilder::new] allows.
+///
+///Entries that do not exist in the lua version that is used are skipped.
+pub const SAFE_STD_LIB: &[&str] = &[
+    "assert",
+    "error",
+    "ipairs",
+    "next",
+    "pairs",
+    "pcall",
+    "print",
+    "rawequal",
+    "rawget",
+    "rawlen",
+    "rawset",
+    "select",
+    "setmetatable",
+    "tonumber",
+    "tostring",
+    "type",
+    "unpack",
+    "xpcall",
+    "coroutine",
+    "math",
+    "string",
+    "table",
+    "utf8",
+];
+
+///Builds a fresh environment table that only contains the instances exported through [ExportInstances]
+///and the parts of the standard library that are explicitly allowed.
+///
+///Library tables like `string` are copied, so scripts running inside the sandbox can not change them for the rest of the lua state.
+///
+///Strings share a single metatable for the whole lua state, which makes the members of `string` available through method syntax like `("x"):rep(3)`.
+///The sandbox leaves that metatable alone, so denied members of `string` stay reachable through `("").method`.
+///```
+///# use tealr::mlu::{SandboxBuilder, ExportInstances, InstanceCollector, mlua::Lua};
+///#[derive(Default)]
+///struct Api;
+///impl ExportInstances for Api {
+///    fn add_instances<T: InstanceCollector>(self, collector: &mut T) -> mlua::Result<()> {
+///        collector.add_instance("answer", |_| Ok(42))?;
+///        Ok(())
+///    }
+///}
+///let lua = Lua::new();
+///let env = SandboxBuilder::new().allow("os.time").build(Api, &lua)?;
+///let code = "assert(io == nil and os.execute == nil) return answer";
+///let answer: i64 = lua.load(code).set_environment(env).eval()?;
+///assert_eq!(answer, 42);
+///# mlua::Result::Ok(())
+///```
+#[derive(Clone, Debug)]
+pub struct SandboxBuilder {
+    allowed: Vec<Cow<'static, str>>,
+    denied: Vec<Cow<'static, str>>,
+}
+
+impl Default for SandboxBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SandboxBuilder {
+    ///creates a builder that allows the entries in [SAFE_STD_LIB]
+    pub fn new() -> Self {
+        Self {
+            allowed: SAFE_STD_LIB.iter().map(|v| Cow::Borrowed(*v)).collect(),
+            denied: Vec::new(),
+        }
+    }
+    ///creates a builder that does not allow anything from the standard library
+    pub fn empty() -> Self {
+        Self {
+            allowed: Vec::new(),
+            denied: Vec::new(),
+        }
+    }
+    ///allows an entry of the standard library. Either a whole library like `os` or a single member like `os.time`
+    pub fn allow(mut self, entry: impl Into<Cow<'static, str>>) -> Self {
+        let entry = entry.into();
+        self.denied.retain(|v| !is_part_of(v, &entry));
+        self.allowed.push(entry);
+        self
+    }
+    ///denies an entry of the standard library, even if it is part of a library that is allowed.
+    ///
+    ///Denying a whole library like `os` also denies the members of it that got allowed before, like `os.time`.
+    pub fn deny(mut self, entry: impl Into<Cow<'static, str>>) -> Self {
+        let entry = entry.into();
+        self.allowed.retain(|v| !is_part_of(v, &entry));
+        self.denied.push(entry);
+        self
+    }
+    ///creates the environment table. Use it with [mlua::Chunk::set_environment] to run code inside of the sandbox.
+    pub fn build<T: ExportInstances>(&self, env: T, lua: &Lua) -> Result<Table> {
+        let table = self.build_std_lib(lua)?;
+        env.add_instances(&mut (table.clone(), lua))?;
+        Ok(table)
+    }
+    ///documents the instances and the available parts of the standard library, describing the environment created by [SandboxBuilder::build] for the same lua state
+    pub fn document<T: ExportInstances>(
+        &self,
+        walker: TypeWalker,
+        lua: &Lua,
+    ) -> Result<TypeWalker> {
+        let std_lib = self.build_std_lib(lua)?;
+        let mut available = Vec::new();
+        for pair in std_lib.pairs::<String, Value>() {
+            let (name, value) = pair?;
+            match value {
+                Value::Table(x) => {
+                    for pair in x.pairs::<String, Value>() {
+                        available.push(format!("{}.{}", name, pair?.0));
+                    }
+                }
+                _ => available.push(name),
+            }
+        }
+        available.sort();
+        let mut walker = walker.document_global_instance::<T>()?;
+        walker.available_std_lib = Some(available);
+        Ok(walker)
+    }
+
+    fn is_denied(&self, path: &str) -> bool {
+        self.denied.iter().any(|v| is_part_of(path, v))
+    }
+
+    fn build_std_lib(&self, lua: &Lua) -> Result<Table> {
+        let globals = lua.globals();
+        let table = lua.create_table()?;
+        for entry in &self.allowed {
+            let mut value = Value::Table(globals.clone());
+            for part in entry.split('.') {
+                value = match value {
+                    Value::Table(x) => x.get(part)?,
+                    _ => Value::Nil,
+                };
+            }
+            let value = match value {
+                Value::Nil => continue,
+                Value::Table(x) => {
+                    let copy = match table.get::<Option<Table>>(entry.as_ref())? {
+                        Some(copy) => copy,
+                        None => lua.create_table()?,
+                    };
+                    for pair in x.pairs::<Value, Value>() {
+                        let (key, value) = pair?;
+                        let is_denied = match &key {
+                            Value::String(key) => {
+                                self.is_denied(&format!("{}.{}", entry, key.to_str()?))
+                            }
+                            _ => false,
+                        };
+                        if !is_denied {
+                            copy.set(key, value)?;
+                        }
+                    }
+                    Value::Table(copy)
+                }
+                x => x,
+            };
+            set_in_namespace(&table, lua, entry, value)?;
+        }
+        Ok(table)
+    }
+}
+
+///whether `path` is `entry` itself or one of its members, like `os.time` is part of `os`
+fn is_part_of(path: &str, entry: &str) -> bool {
+    path.strip_prefix(entry)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
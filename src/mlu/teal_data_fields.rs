@@ -1,6 +1,6 @@
 use mlua::{AnyUserData, FromLua, IntoLua, Lua, MetaMethod};
 
-use crate::ToTypename;
+use crate::{Placement, Stability, ToLuaLiteral, ToTypename};
 
 use super::{MaybeSend, TealData};
 
@@ -14,6 +14,31 @@ use super::{MaybeSend, TealData};
 pub trait TealDataFields<T: TealData> {
     ///Adds documentation to the next field that gets added
     fn document(&mut self, documentation: &str);
+    ///Marks the next field that gets added as deprecated.
+    ///
+    ///Using it from lua triggers the hook set with [set_deprecation_hook](crate::mlu::set_deprecation_hook) once.
+    ///The default implementation ignores it.
+    fn deprecate(&mut self, reason: &str, since: Option<&str>) {
+        let _ = (reason, since);
+    }
+    ///Sets the version in which the next field that gets added was introduced
+    ///
+    ///The default implementation ignores it.
+    fn since(&mut self, version: &str) {
+        let _ = version;
+    }
+    ///Sets how stable the next field that gets added is
+    ///
+    ///The default implementation ignores it.
+    fn stability(&mut self, stability: Stability) {
+        let _ = stability;
+    }
+    ///Sets if the next static field that gets added is documented on the type, its [UserDataProxy](crate::mlu::UserDataProxy) or both
+    ///
+    ///The default implementation ignores it, documenting the field on both.
+    fn placement(&mut self, placement: Placement) {
+        let _ = placement;
+    }
     /// the teal version of [UserDataFields](mlua::UserDataFields::add_field_method_get)
     fn add_field_method_get<S, R, M>(&mut self, name: S, method: M)
     where
@@ -38,6 +63,14 @@ pub trait TealDataFields<T: TealData> {
         S: ToString + AsRef<str>,
         A: FromLua + ToTypename,
         F: 'static + MaybeSend + FnMut(&Lua, AnyUserData, A) -> mlua::Result<()>;
+    ///Adds a field that always returns the same value. Unlike the other fields, the value itself also gets documented.
+    fn add_constant<S, V>(&mut self, name: S, value: V)
+    where
+        S: AsRef<str> + ToString,
+        V: 'static + MaybeSend + Clone + IntoLua + ToTypename + ToLuaLiteral,
+    {
+        self.add_field_function_get(name, move |_, _| Ok(value.clone()))
+    }
     /// the teal version of [UserDataFields](mlua::UserDataFields::add_meta_field_with)
     fn add_meta_field_with<R, F>(&mut self, meta: MetaMethod, f: F)
     where
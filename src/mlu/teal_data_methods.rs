@@ -1,8 +1,15 @@
-use mlua::{FromLuaMulti, IntoLua as ToLua, IntoLuaMulti as ToLuaMulti, Lua, MetaMethod, Result};
+use mlua::{
+    FromLua, FromLuaMulti, IntoLua as ToLua, IntoLuaMulti as ToLuaMulti, Lua, MetaMethod, Result,
+};
 
-use crate::{TealMultiValue, ToTypename};
+use crate::{Deprecation, Placement, Stability, TealMultiValue, ToLuaLiteral, ToTypename};
 
-use super::MaybeSend;
+use super::{
+    deprecation_hook::{
+        deprecated_global, set_next_global_deprecation, take_next_global_deprecation,
+    },
+    MaybeSend, TypedFunction,
+};
 
 ///The teal version of [UserDataMethods](mlua::UserDataMethods)
 ///
@@ -91,13 +98,191 @@ pub trait TealDataMethods<T: ToTypename> {
     fn document(&mut self, documentation: &str) -> &mut Self;
     ///Adds documentation for this type itself. They will be written right above the record in the .d.tl file
     fn document_type(&mut self, documentation: &str) -> &mut Self;
+    ///Documents a parameter of the next method/function that gets added
+    ///
+    ///The default implementation adds it to the documentation as text.
+    fn document_param(&mut self, name: &str, documentation: &str) -> &mut Self {
+        self.document(&format!("`{}`: {}", name, documentation))
+    }
+    ///Documents a return value of the next method/function that gets added.
+    ///
+    ///Call it once for every return value, in order.
+    ///The default implementation adds it to the documentation as text.
+    fn document_return(&mut self, documentation: &str) -> &mut Self {
+        self.document(&format!("Returns: {}", documentation))
+    }
+    ///Documents a way in which the next method/function that gets added can fail
+    ///
+    ///The default implementation adds it to the documentation as text.
+    fn document_error(&mut self, documentation: &str) -> &mut Self {
+        self.document(&format!("Errors: {}", documentation))
+    }
+    ///Adds example code to the next method/function that gets added
+    ///
+    ///The default implementation adds it to the documentation as a lua code block.
+    fn document_example(&mut self, code: &str) -> &mut Self {
+        self.document(&format!("```lua\n{}\n```", code.trim_end()))
+    }
+    ///Marks the next method/function that gets added as deprecated.
+    ///
+    ///Calling it from lua triggers the hook set with [set_deprecation_hook](crate::mlu::set_deprecation_hook) once.
+    ///The default implementation ignores it.
+    fn deprecate(&mut self, reason: &str, since: Option<&str>) -> &mut Self {
+        let _ = (reason, since);
+        self
+    }
+    ///Sets the version in which the next method/function that gets added was introduced
+    ///
+    ///The default implementation ignores it.
+    fn since(&mut self, version: &str) -> &mut Self {
+        let _ = version;
+        self
+    }
+    ///Sets how stable the next method/function that gets added is
+    ///
+    ///The default implementation ignores it.
+    fn stability(&mut self, stability: Stability) -> &mut Self {
+        let _ = stability;
+        self
+    }
+    ///Sets if the next static function that gets added is documented on the type, its [UserDataProxy](crate::mlu::UserDataProxy) or both
+    ///
+    ///The default implementation ignores it, documenting the function on both.
+    fn placement(&mut self, placement: Placement) -> &mut Self {
+        let _ = placement;
+        self
+    }
     ///generates a `.help()` function on lua's/teals side, which can be used at run time to view the documentation.
+    ///
+    ///It documents the members that got added before it, so call it last.
     fn generate_help(&mut self);
+    #[cfg(feature = "derive")]
+    ///generates a `.__tealr_type()` function on lua's/teals side, which returns the [RecordGenerator](crate::RecordGenerator) of this type.
+    ///
+    ///This makes it possible to inspect the fields and methods of a value at run time, for example to autocomplete them in a REPL.
+    fn generate_type_info(&mut self);
+    ///Adds an `__index` meta method that gets used for every key that isn't a field or method.
+    ///
+    ///Use it for members that are only known at run time, like the components of an entity.
+    ///Returning `None` makes the lookup result in `nil`.
+    ///The type gets documented as having both its named members and a `{K: V}` indexer.
+    ///Implementations have to keep the fields and methods reachable, only using `index` for the keys that aren't one of them.
+    ///```
+    ///# use std::collections::HashMap;
+    ///# use tealr::{mlu::{TealData, TealDataFields, TealDataMethods, UserData}, ToTypename};
+    ///#[derive(Clone, UserData, ToTypename)]
+    ///struct Entity {
+    ///    id: i64,
+    ///    components: HashMap<String, i64>,
+    ///}
+    ///impl TealData for Entity {
+    ///    fn add_methods<T: TealDataMethods<Self>>(methods: &mut T) {
+    ///        methods.add_dynamic_index(|_, this, key: String| Ok(this.components.get(&key).copied()));
+    ///    }
+    ///    fn add_fields<F: TealDataFields<Self>>(fields: &mut F) {
+    ///        fields.add_field_method_get("id", |_, this| Ok(this.id));
+    ///    }
+    ///}
+    ///```
+    fn add_dynamic_index<K, V, F>(&mut self, index: F)
+    where
+        K: FromLua + ToTypename,
+        V: ToLua + ToTypename,
+        F: 'static + MaybeSend + Fn(&Lua, &T, K) -> Result<Option<V>>;
+    ///Registers the function that creates new values of this type.
+    ///
+    ///The constructor is exposed as the static function `new` and through `__call`,
+    ///so the class proxy created with [UserDataProxy](crate::mlu::UserDataProxy) can be called directly, like `Vector(1, 2, 3)`.
+    ///Only the proxy is callable, values of the type itself are not.
+    ///The default implementation only adds the `new` function, so the proxy isn't callable.
+    ///The documentation of the next method/function that gets added applies to it.
+    ///```
+    ///# use tealr::{mlu::{TealData, TealDataMethods, UserData}, ToTypename};
+    ///#[derive(Clone, UserData, ToTypename)]
+    ///struct Vector {
+    ///    x: f64,
+    ///    y: f64,
+    ///}
+    ///impl TealData for Vector {
+    ///    fn add_methods<T: TealDataMethods<Self>>(methods: &mut T) {
+    ///        methods.document("Creates a new vector");
+    ///        methods.add_constructor(|_, (x, y): (f64, f64)| Ok(Vector { x, y }));
+    ///    }
+    ///}
+    ///```
+    fn add_constructor<A, R, F>(&mut self, constructor: F)
+    where
+        A: FromLuaMulti + TealMultiValue,
+        R: ToLuaMulti + TealMultiValue,
+        F: 'static + MaybeSend + Fn(&Lua, A) -> Result<R>,
+    {
+        self.add_function("new", constructor)
+    }
+    ///Makes it possible to loop over the type from lua, using the iterator returned by the given function.
+    ///
+    ///This uses the `__pairs` meta method, so `for k, v in pairs(value) do` works.
+    ///On luau `__iter` is used instead, so `for k, v in value do` works.
+    ///Lua 5.1 and LuaJIT have no meta method for this, so a `pairs` method gets added instead, used like `for k, v in value:pairs() do`.
+    ///
+    ///The types of the keys and values are documented as the return types of the function that gets iterated over.
+    ///```
+    ///# use tealr::{mlu::{TealData, TealDataMethods, UserData}, ToTypename};
+    ///#[derive(Clone, UserData, ToTypename)]
+    ///struct Inventory(Vec<String>);
+    ///impl TealData for Inventory {
+    ///    fn add_methods<T: TealDataMethods<Self>>(methods: &mut T) {
+    ///        methods.add_iterator(|this| {
+    ///            this.0.clone().into_iter().enumerate().map(|(k, v)| (k + 1, v))
+    ///        });
+    ///    }
+    ///}
+    ///```
+    fn add_iterator<K, V, I, F>(&mut self, iterator: F)
+    where
+        K: ToLua + ToTypename,
+        V: ToLua + ToTypename,
+        I: IntoIterator<Item = (K, V)>,
+        I::IntoIter: 'static + MaybeSend,
+        F: 'static + MaybeSend + Fn(&T) -> I,
+    {
+        let method = move |lua: &Lua, this: &T, ()| {
+            let mut iter = iterator(this).into_iter();
+            TypedFunction::<(), (Option<K>, Option<V>)>::from_rust_mut(
+                move |_, ()| {
+                    Ok(match iter.next() {
+                        Some((key, value)) => (Some(key), Some(value)),
+                        None => (None, None),
+                    })
+                },
+                lua,
+            )
+        };
+        #[cfg(feature = "mlua_luau")]
+        self.add_meta_method(MetaMethod::Iter, method);
+        #[cfg(any(
+            feature = "mlua_lua54",
+            feature = "mlua_lua53",
+            feature = "mlua_lua52",
+            feature = "mlua_luajit52"
+        ))]
+        self.add_meta_method(MetaMethod::Pairs, method);
+        #[cfg(not(any(
+            feature = "mlua_luau",
+            feature = "mlua_lua54",
+            feature = "mlua_lua53",
+            feature = "mlua_lua52",
+            feature = "mlua_luajit52"
+        )))]
+        self.add_method("pairs", method);
+    }
 }
 
 ///collects every instance that a type has
 pub trait InstanceCollector {
     ///adds an instance
+    ///
+    ///The name can be a dotted path like `game.physics.raycast`,
+    ///in which case the instance is stored in nested tables that get created when needed.
     fn add_instance<P, T, F>(&mut self, global_name: P, instance: F) -> Result<&mut Self>
     where
         P: Into<String>,
@@ -105,6 +290,86 @@ pub trait InstanceCollector {
         F: FnOnce(&Lua) -> mlua::Result<T>;
     ///Adds documentation to the next global instance
     fn document_instance(&mut self, doc: &'static str) -> &mut Self;
+    ///Marks the next global instance as deprecated
+    ///
+    ///Calling it from lua triggers the hook set with [set_deprecation_hook](crate::mlu::set_deprecation_hook) once, if it is a function.
+    ///The default implementation ignores it.
+    fn deprecate(&mut self, reason: &str, since: Option<&str>) -> &mut Self {
+        let _ = (reason, since);
+        self
+    }
+    ///Sets the version in which the next global instance was introduced
+    ///
+    ///The default implementation ignores it.
+    fn since(&mut self, version: &str) -> &mut Self {
+        let _ = version;
+        self
+    }
+    ///Sets how stable the next global instance is
+    ///
+    ///The default implementation ignores it.
+    fn stability(&mut self, stability: Stability) -> &mut Self {
+        let _ = stability;
+        self
+    }
+    ///adds a constant. Unlike [add_instance](InstanceCollector::add_instance) the value itself also gets documented.
+    fn add_constant<P, T>(&mut self, global_name: P, value: T) -> Result<&mut Self>
+    where
+        P: Into<String>,
+        T: ToTypename + ToLua + ToLuaLiteral,
+    {
+        self.add_instance(global_name, |_| Ok(value))
+    }
+    ///Exposes a function, without needing to wrap it in a [TypedFunction](crate::mlu::TypedFunction) first.
+    ///
+    ///Use [mlua_create_named_parameters](crate::mlua_create_named_parameters) as the parameter type to give the parameters a name.
+    fn add_function<P, A, R, F>(&mut self, global_name: P, function: F) -> Result<&mut Self>
+    where
+        P: Into<String>,
+        A: FromLuaMulti + TealMultiValue,
+        R: ToLuaMulti + TealMultiValue,
+        F: 'static + MaybeSend + Fn(&Lua, A) -> Result<R>,
+    {
+        self.add_instance(global_name, |lua| {
+            crate::mlu::TypedFunction::<A, R>::from_rust(function, lua)
+        })
+    }
+    ///Exposes a mutable function, without needing to wrap it in a [TypedFunction](crate::mlu::TypedFunction) first.
+    fn add_function_mut<P, A, R, F>(&mut self, global_name: P, function: F) -> Result<&mut Self>
+    where
+        P: Into<String>,
+        A: FromLuaMulti + TealMultiValue,
+        R: ToLuaMulti + TealMultiValue,
+        F: 'static + MaybeSend + FnMut(&Lua, A) -> Result<R>,
+    {
+        self.add_instance(global_name, |lua| {
+            crate::mlu::TypedFunction::<A, R>::from_rust_mut(function, lua)
+        })
+    }
+    ///Exposes a global `help(value, key)` function that shows the documentation of any value that has a generated `help` function.
+    ///
+    ///Instead of a value, the dotted name of a global can be given, like `help("game.player.teleport")`.
+    fn add_help(&mut self) -> Result<&mut Self> {
+        self.add_function("help", super::help::global_help)
+    }
+    #[cfg(feature = "mlua_async")]
+    ///Exposes an async function, without needing to wrap it in a [TypedFunction](crate::mlu::TypedFunction) first.
+    fn add_async_function<P, A, R, F, FR>(
+        &mut self,
+        global_name: P,
+        function: F,
+    ) -> Result<&mut Self>
+    where
+        P: Into<String>,
+        A: FromLuaMulti + TealMultiValue,
+        R: ToLuaMulti + TealMultiValue,
+        F: Fn(Lua, A) -> FR + MaybeSend + 'static,
+        FR: std::future::Future<Output = Result<R>> + mlua::MaybeSend + 'static,
+    {
+        self.add_instance(global_name, |lua| {
+            crate::mlu::TypedFunction::<A, R>::from_rust_async(function, lua)
+        })
+    }
 }
 
 ///used to export instances to lua
@@ -114,6 +379,59 @@ pub fn set_global_env<T: ExportInstances>(env: T, lua: &Lua) -> Result<()> {
     Ok(())
 }
 
+///used to export instances to lua as a module, which can then be loaded using `require(module_name)`.
+///
+///The instances are stored in `package.loaded` and, if the lua version has it, `package.preload`.
+pub fn set_module_env<T: ExportInstances>(module_name: &str, env: T, lua: &Lua) -> Result<()> {
+    let module = lua.create_table()?;
+    env.add_instances(&mut (module.clone(), lua))?;
+    let package: mlua::Table = lua.globals().get("package")?;
+    if let Some(preload) = package.get::<Option<mlua::Table>>("preload")? {
+        let loader_module = module.clone();
+        preload.set(
+            module_name,
+            lua.create_function(move |_, ()| Ok(loader_module.clone()))?,
+        )?;
+    }
+    let loaded: mlua::Table = package.get("loaded")?;
+    loaded.set(module_name, module)?;
+    Ok(())
+}
+
+///sets `value` at the given dotted path inside of `table`, creating the tables along the path if needed.
+pub(crate) fn set_in_namespace(
+    table: &mlua::Table,
+    lua: &Lua,
+    path: &str,
+    value: impl ToLua,
+) -> Result<()> {
+    match path.rsplit_once('.') {
+        Some((namespace, name)) => {
+            let mut table = table.clone();
+            for part in namespace.split('.') {
+                table = match table.get::<mlua::Value>(part)? {
+                    mlua::Value::Nil => {
+                        let new_table = lua.create_table()?;
+                        table.set(part, &new_table)?;
+                        new_table
+                    }
+                    mlua::Value::Table(x) => x,
+                    x => {
+                        return Err(mlua::Error::RuntimeError(format!(
+                            "Can not use `{}` as a namespace for `{}`, it is already a {}",
+                            part,
+                            path,
+                            x.type_name()
+                        )))
+                    }
+                };
+            }
+            table.set(name, value)
+        }
+        None => table.set(path, value),
+    }
+}
+
 impl InstanceCollector for (mlua::Table, &Lua) {
     fn add_instance<P, T, F>(&mut self, global_name: P, instance: F) -> Result<&mut Self>
     where
@@ -121,13 +439,30 @@ impl InstanceCollector for (mlua::Table, &Lua) {
         T: ToTypename + ToLua,
         F: FnOnce(&Lua) -> Result<T>,
     {
-        let instance = instance(self.1)?;
-        self.0.set(global_name.into(), instance)?;
+        let global_name = global_name.into();
+        let instance = instance(self.1)?.into_lua(self.1)?;
+        let instance = match take_next_global_deprecation(self.1) {
+            Some(deprecation) => {
+                deprecated_global(self.1, global_name.clone(), deprecation, instance)?
+            }
+            None => instance,
+        };
+        set_in_namespace(&self.0, self.1, &global_name, instance)?;
         Ok(self)
     }
     fn document_instance(&mut self, _: &'static str) -> &mut Self {
         self
     }
+    fn deprecate(&mut self, reason: &str, since: Option<&str>) -> &mut Self {
+        set_next_global_deprecation(self.1, Deprecation::new(reason, since));
+        self
+    }
+    fn since(&mut self, _: &str) -> &mut Self {
+        self
+    }
+    fn stability(&mut self, _: Stability) -> &mut Self {
+        self
+    }
 }
 
 ///implement this to easily document what global instances are exposed to lua
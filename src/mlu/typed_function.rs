@@ -119,6 +119,19 @@ where
             _r: PhantomData,
         })
     }
+    #[cfg(feature = "mlua_async")]
+    ///make a typed function directly from an async Rust one.
+    pub fn from_rust_async<Func, FR>(func: Func, lua: &Lua) -> mlua::Result<Self>
+    where
+        Func: 'static + crate::mlu::MaybeSend + Fn(Lua, Params) -> FR,
+        FR: 'static + mlua::MaybeSend + std::future::Future<Output = mlua::Result<Response>>,
+    {
+        Ok(Self {
+            inner_function: lua.create_async_function(func)?,
+            _p: PhantomData,
+            _r: PhantomData,
+        })
+    }
 }
 impl<Params, Response> TypedFunction<Params, Response>
 where
@@ -1,29 +1,42 @@
 use std::marker::PhantomData;
 
-use mlua::{AnyUserData, Error, IntoLua, Lua, UserData};
+use mlua::{AnyUserData, Error, Function, IntoLua, Lua, MetaMethod, UserData};
 
 use crate::{
-    type_to_string, type_to_teal_parts, EnumGenerator, RecordGenerator, ToTypename, Type, TypeBody,
+    type_to_string, type_to_teal_parts, EnumGenerator, FunctionParam, Placement, RecordGenerator,
+    TaggedUnionGenerator, ToTypename, Type, TypeBody,
 };
 
+///the meta function registered by `add_constructor`, which becomes `__call` on the proxy
+pub(crate) const PROXY_CALL: &str = "__tealr_proxy_call";
+
 /// A userdata which can be used as a static proxy
 pub trait StaticUserdata: UserData + 'static {}
 impl<T: UserData + 'static> StaticUserdata for T {}
 
 /// A newtype storing proxy userdata created via [`mlua::Lua::create_proxy`].
 ///
-/// the `TypeName` for this struct is implemented as the `TypeName` for `T` concatenated with "Class".
-/// For example, if your type is called "MyType", the proxy would have "MyTypeClass" for a `TypeName`.
+/// the `TypeName` for this struct is implemented as "Class" concatenated with the `TypeName` for `T`.
+/// For example, if your type is called "MyType", the proxy would have "ClassMyType" for a `TypeName`.
+/// Use [ToTypename::PROXY_NAME] (or `#[tealr(proxy_name = "...")]` when deriving `ToTypename`) to pick a different name.
 ///
-/// the documentation for this proxy receives only `static` methods, i.e. those created via:
+/// the documentation for this proxy receives only `static` members, i.e. those created via:
 /// - `TealDataMethods::add_function`
 /// - `TealDataMethods::add_meta_function`
 /// - `TealDataMethods::add_meta_function_mut`
 /// - `TealDataMethods::add_function_mut`
 /// - `TealDataMethods::add_async_function`
+/// - `TealDataMethods::add_constructor`
 /// - `TealDataFields::add_field_function_get`
 /// - `TealDataFields::add_field_function_set`
 /// - `TealDataFields::add_meta_field_with`
+/// - `TealDataFields::add_constant`
+///
+/// Use `placement` on [TealDataMethods](crate::mlu::TealDataMethods::placement) and [TealDataFields](crate::mlu::TealDataFields::placement)
+/// to only document a static member on either the proxy or the type itself.
+/// [TypeWalker::process_type](crate::TypeWalker::process_type) leaves the members that are only meant for the proxy out of the type itself.
+///
+/// If the type has a constructor registered with `TealDataMethods::add_constructor`, the proxy can be called to create new values.
 ///
 /// The type documentation is overriden as well.
 pub struct UserDataProxy<T: StaticUserdata> {
@@ -34,8 +47,14 @@ pub struct UserDataProxy<T: StaticUserdata> {
 impl<T: StaticUserdata> UserDataProxy<T> {
     /// Creates a new UserDataProxy
     pub fn new(lua: &Lua) -> Result<Self, Error> {
+        let user_data = lua.create_proxy::<T>()?;
+        //values of `T` share their methods with the proxy, but only the proxy can be called
+        let metatable = user_data.metatable()?;
+        if let Some(call) = metatable.get::<Option<Function>>(PROXY_CALL)? {
+            metatable.set(MetaMethod::Call, call)?;
+        }
         Ok(Self {
-            user_data: lua.create_proxy::<T>()?,
+            user_data,
             ph_: Default::default(),
         })
     }
@@ -45,7 +64,10 @@ impl<T: StaticUserdata + ToTypename> ToTypename for UserDataProxy<T> {
     fn to_typename() -> crate::Type {
         let mut x = T::to_typename();
         if let Type::Single(x) = &mut x {
-            x.name = format!("Class{}", x.name).into();
+            x.name = match T::PROXY_NAME {
+                Some(name) => name.into(),
+                None => format!("Class{}", x.name).into(),
+            };
         }
         x
     }
@@ -63,16 +85,35 @@ impl<T: StaticUserdata + TypeBody + ToTypename> TypeBody for UserDataProxy<T> {
             .join("");
         match generator {
             crate::TypeGenerator::Record(record_generator) => {
-                crate::TypeGenerator::Record(Box::new(RecordGenerator {
+                let mut record = RecordGenerator {
+                    ty: Self::to_typename(),
                     // we overwrite anything which is not static
                     type_doc: format!("Collection of static methods for [`{}`].", type_name_string),
-                    fields: Default::default(),
                     methods: Default::default(),
                     mut_methods: Default::default(),
                     meta_method: Default::default(),
                     meta_method_mut: Default::default(),
-                    ..record_generator.as_ref().clone()
-                }))
+                    dynamic_index: None,
+                    ..*record_generator
+                };
+                // constants are the same for every value, so they are static as well
+                record.fields.retain(|v| v.value.is_some());
+                record.retain_placement(Placement::Proxy);
+                if let Some(constructor) = &record.constructor {
+                    //calling the proxy passes the proxy itself as the first argument
+                    let mut call = constructor.clone();
+                    call.name = MetaMethod::Call.name().to_owned().into();
+                    call.is_meta_method = true;
+                    call.params.insert(
+                        0,
+                        FunctionParam {
+                            param_name: Some("self".into()),
+                            ty: Self::to_typename(),
+                        },
+                    );
+                    record.meta_function.push(call);
+                }
+                crate::TypeGenerator::Record(Box::new(record))
             }
             crate::TypeGenerator::Enum(enum_generator) => {
                 crate::TypeGenerator::Enum(EnumGenerator {
@@ -80,6 +121,12 @@ impl<T: StaticUserdata + TypeBody + ToTypename> TypeBody for UserDataProxy<T> {
                     ..enum_generator
                 })
             }
+            crate::TypeGenerator::TaggedUnion(tagged_union) => {
+                crate::TypeGenerator::TaggedUnion(TaggedUnionGenerator {
+                    name: type_to_string(&T::to_typename(), false),
+                    ..tagged_union
+                })
+            }
         }
     }
 }
@@ -1,14 +1,20 @@
-use bstr::{ByteSlice, ByteVec};
 #[cfg(feature = "mlua_async")]
 use mlua::UserDataRef;
 use mlua::{
-    FromLuaMulti, IntoLuaMulti as ToLuaMulti, Lua, MetaMethod, Result, UserData, UserDataFields,
-    UserDataMethods,
+    AnyUserData, FromLua, FromLuaMulti, Function, IntoLua as ToLua, IntoLuaMulti as ToLuaMulti,
+    Lua, MetaMethod, MultiValue, ObjectLike, Result, UserData, UserDataFields, UserDataMethods,
+    UserDataRegistry,
 };
 use std::{collections::HashMap, marker::PhantomData};
 
-use super::{MaybeSend, TealData, TealDataFields, TealDataMethods};
-use crate::{type_generator::get_method_data, type_to_string, TealMultiValue, ToTypename};
+use super::{
+    deprecation_hook::warn_deprecated, help::HelpPages, user_data_proxy::PROXY_CALL, MaybeSend,
+    TealData, TealDataFields, TealDataMethods,
+};
+use crate::{
+    type_to_string, Deprecation, MapRepresentation, Placement, RecordGenerator, Stability,
+    TealMultiValue, ToTypename,
+};
 
 ///Used to turn [UserDataMethods](mlua::UserDataMethods) into [TealDataMethods](crate::mlu::TealDataMethods).
 ///
@@ -21,9 +27,7 @@ where
 {
     cont: &'a mut Container,
     _t: PhantomData<T>,
-    documentation: HashMap<Vec<u8>, String>,
-    type_doc: String,
-    next_docs: Option<String>,
+    record: Option<RecordGenerator>,
 }
 impl<'a, Container, T> UserDataWrapper<'a, Container, T>
 where
@@ -31,6 +35,8 @@ where
     T: UserData,
 {
     ///wraps the [UserDataMethods](mlua::UserDataMethods) so it can be used by [TealData](crate::mlu::TealData) to set methods.
+    ///
+    ///Fields aren't part of the [generated help](TealDataMethods::generate_help), use [from_user_data_registry](UserDataWrapper::from_user_data_registry) to include them.
     ///```
     ///# use std::borrow::Cow;
     ///# use mlua::{Lua, Result, UserData, UserDataMethods};
@@ -54,9 +60,41 @@ where
         Self {
             cont,
             _t: PhantomData,
-            documentation: Default::default(),
-            next_docs: Default::default(),
-            type_doc: Default::default(),
+            record: None,
+        }
+    }
+}
+impl<'a, T> UserDataWrapper<'a, UserDataRegistry<T>, T>
+where
+    T: UserData,
+{
+    ///wraps the [UserDataRegistry](mlua::UserDataRegistry) so it can be used by [TealData](crate::mlu::TealData) to set both fields and methods.
+    ///
+    ///Unlike wrapping the fields and methods separately, the documentation of the fields is kept when the methods get added,
+    ///so [generate_help](TealDataMethods::generate_help) includes the fields.
+    ///```
+    ///# use mlua::{UserData, UserDataRegistry};
+    ///# use tealr::{Type, mlu::{TealData, UserDataWrapper}, ToTypename};
+    /// struct Example {}
+    /// impl TealData for Example {}
+    /// impl ToTypename for Example {
+    ///     fn to_typename() -> Type {
+    ///         Type::new_single("Example", tealr::KindOfType::External)
+    ///     }
+    /// }
+    /// impl UserData for Example {
+    ///     fn register(registry: &mut UserDataRegistry<Self>) {
+    ///         let mut x = UserDataWrapper::from_user_data_registry(registry);
+    ///         <Self as TealData>::add_fields(&mut x);
+    ///         <Self as TealData>::add_methods(&mut x);
+    ///     }
+    ///}
+    ///```
+    pub fn from_user_data_registry(cont: &'a mut UserDataRegistry<T>) -> Self {
+        Self {
+            cont,
+            _t: PhantomData,
+            record: None,
         }
     }
 }
@@ -89,51 +127,42 @@ where
         Self {
             cont,
             _t: PhantomData,
-            documentation: Default::default(),
-            next_docs: Default::default(),
-            type_doc: Default::default(),
+            record: None,
         }
     }
 }
 impl<Container, T: ToTypename> UserDataWrapper<'_, Container, T>
 where
     T: UserData,
-    //Container: UserDataMethods<T>,
 {
-    fn copy_method_docs<A, R>(&mut self, to: &str, self_type: bool)
-    where
-        A: FromLuaMulti + TealMultiValue,
-        R: ToLuaMulti + TealMultiValue,
-    {
-        let type_def = get_method_data::<A, R, _>(to, false, self_type.then(|| T::to_typename()));
-        let generated = type_to_string(&type_def.into_type(), false);
-
-        let docs = generated + "\n\ndocs:\n" + &self.next_docs.take().unwrap_or_default();
-        let documentation = &mut self.documentation;
-        documentation.insert(to.as_bytes().to_owned(), docs);
-    }
-    fn copy_field_docs<F: ToTypename>(&mut self, name: &str) {
-        let name = name.as_bytes().to_vec();
-        let documentation = &mut self.documentation;
-        let mut current_doc = documentation.remove(&name).unwrap_or_else(|| {
-            let mut str = type_to_string(&F::to_typename(), false);
-            str.push_str("\n\n docs:\n");
-            str
-        });
-        current_doc.push_str(&self.next_docs.take().unwrap_or_default());
-        documentation.insert(name, current_doc);
-    }
-    fn document(&mut self, documentation: &str) {
-        match &mut self.next_docs {
-            Some(x) => {
-                x.push('\n');
-                x.push_str(documentation)
-            }
-            None => self.next_docs = Some(documentation.to_owned()),
-        };
+    ///the documentation of everything added so far, used by [generate_help](TealDataMethods::generate_help) and [generate_type_info](TealDataMethods::generate_type_info)
+    fn record(&mut self) -> &mut RecordGenerator {
+        self.record.get_or_insert_with(new_record::<T>)
     }
 }
 
+fn new_record<T: ToTypename>() -> RecordGenerator {
+    let mut record = RecordGenerator::new::<T>(false);
+    record.is_user_data = true;
+    record
+}
+
+///documents a member with `document` and, if it is deprecated, returns the name and deprecation marker needed to warn when it gets used
+fn document_member(
+    record: &mut RecordGenerator,
+    name: &str,
+    document: impl FnOnce(&mut RecordGenerator),
+) -> Option<(String, Deprecation)> {
+    let deprecation = record.next_deprecation.clone();
+    document(record);
+    deprecation.map(|deprecation| {
+        (
+            format!("{}.{}", type_to_string(&record.ty, false), name),
+            deprecation,
+        )
+    })
+}
+
 impl<Container, T: ToTypename> TealDataMethods<T> for UserDataWrapper<'_, Container, T>
 where
     T: UserData,
@@ -147,19 +176,37 @@ where
         R: ToLuaMulti + TealMultiValue,
         M: 'static + MaybeSend + Fn(&Lua, &T, A) -> Result<R>,
     {
-        self.copy_method_docs::<A, R>(name.as_ref(), true);
-        self.cont.add_method(name, method)
+        let deprecation = document_member(self.record(), name.as_ref(), |record| {
+            record.add_method::<_, A, R, T>(name.as_ref())
+        });
+        match deprecation {
+            Some((full_name, deprecation)) => self.cont.add_method(name, move |lua, this, args| {
+                warn_deprecated(lua, &full_name, &deprecation)?;
+                method(lua, this, args)
+            }),
+            None => self.cont.add_method(name, method),
+        }
     }
     #[inline(always)]
-    fn add_method_mut<S, A, R, M>(&mut self, name: S, method: M)
+    fn add_method_mut<S, A, R, M>(&mut self, name: S, mut method: M)
     where
         S: ToString + AsRef<str>,
         A: FromLuaMulti + TealMultiValue,
         R: ToLuaMulti + TealMultiValue,
         M: 'static + MaybeSend + FnMut(&Lua, &mut T, A) -> Result<R>,
     {
-        self.copy_method_docs::<A, R>(name.as_ref(), true);
-        self.cont.add_method_mut(name, method)
+        let deprecation = document_member(self.record(), name.as_ref(), |record| {
+            record.add_method_mut::<_, A, R, T>(name.as_ref())
+        });
+        match deprecation {
+            Some((full_name, deprecation)) => {
+                self.cont.add_method_mut(name, move |lua, this, args| {
+                    warn_deprecated(lua, &full_name, &deprecation)?;
+                    method(lua, this, args)
+                })
+            }
+            None => self.cont.add_method_mut(name, method),
+        }
     }
     #[cfg(feature = "mlua_async")]
     #[inline(always)]
@@ -171,8 +218,22 @@ where
         MR: std::future::Future<Output = Result<R>> + mlua::MaybeSend + 'static,
         R: ToLuaMulti + TealMultiValue,
     {
-        self.copy_method_docs::<A, R>(name.as_ref(), true);
-        self.cont.add_async_method(name, method)
+        let deprecation = document_member(self.record(), name.as_ref(), |record| {
+            record.add_method::<_, A, R, T>(name.as_ref())
+        });
+        match deprecation {
+            Some((full_name, deprecation)) => {
+                self.cont.add_async_method(name, move |lua, this, args| {
+                    let warned = warn_deprecated(&lua, &full_name, &deprecation);
+                    let future = method(lua, this, args);
+                    async move {
+                        warned?;
+                        future.await
+                    }
+                })
+            }
+            None => self.cont.add_async_method(name, method),
+        }
     }
     #[inline(always)]
     fn add_function<S, A, R, F>(&mut self, name: S, function: F)
@@ -182,19 +243,35 @@ where
         R: ToLuaMulti + TealMultiValue,
         F: 'static + MaybeSend + Fn(&Lua, A) -> Result<R>,
     {
-        self.copy_method_docs::<A, R>(name.as_ref(), false);
-        self.cont.add_function(name, function)
+        let deprecation = document_member(self.record(), name.as_ref(), |record| {
+            record.add_function::<_, A, R>(name.as_ref())
+        });
+        match deprecation {
+            Some((full_name, deprecation)) => self.cont.add_function(name, move |lua, args| {
+                warn_deprecated(lua, &full_name, &deprecation)?;
+                function(lua, args)
+            }),
+            None => self.cont.add_function(name, function),
+        }
     }
     #[inline(always)]
-    fn add_function_mut<S, A, R, F>(&mut self, name: S, function: F)
+    fn add_function_mut<S, A, R, F>(&mut self, name: S, mut function: F)
     where
         S: ToString + AsRef<str>,
         A: FromLuaMulti + TealMultiValue,
         R: ToLuaMulti + TealMultiValue,
         F: 'static + MaybeSend + FnMut(&Lua, A) -> Result<R>,
     {
-        self.copy_method_docs::<A, R>(name.as_ref(), false);
-        self.cont.add_function_mut(name, function)
+        let deprecation = document_member(self.record(), name.as_ref(), |record| {
+            record.add_function_mut::<_, A, R>(name.as_ref())
+        });
+        match deprecation {
+            Some((full_name, deprecation)) => self.cont.add_function_mut(name, move |lua, args| {
+                warn_deprecated(lua, &full_name, &deprecation)?;
+                function(lua, args)
+            }),
+            None => self.cont.add_function_mut(name, function),
+        }
     }
     #[cfg(feature = "mlua_async")]
     #[inline(always)]
@@ -206,8 +283,22 @@ where
         F: Fn(Lua, A) -> FR + mlua::MaybeSend + 'static,
         FR: std::future::Future<Output = Result<R>> + mlua::MaybeSend + 'static,
     {
-        self.copy_method_docs::<A, R>(name.as_ref(), false);
-        self.cont.add_async_function(name, function)
+        let deprecation = document_member(self.record(), name.as_ref(), |record| {
+            record.add_function::<_, A, R>(name.as_ref())
+        });
+        match deprecation {
+            Some((full_name, deprecation)) => {
+                self.cont.add_async_function(name, move |lua, args| {
+                    let warned = warn_deprecated(&lua, &full_name, &deprecation);
+                    let future = function(lua, args);
+                    async move {
+                        warned?;
+                        future.await
+                    }
+                })
+            }
+            None => self.cont.add_async_function(name, function),
+        }
     }
     #[inline(always)]
     fn add_meta_method<A, R, M>(&mut self, meta: MetaMethod, method: M)
@@ -216,18 +307,40 @@ where
         R: ToLuaMulti + TealMultiValue,
         M: 'static + MaybeSend + Fn(&Lua, &T, A) -> Result<R>,
     {
-        self.copy_method_docs::<A, R>(meta.name(), true);
-        self.cont.add_meta_method(meta, method)
+        let deprecation = document_member(self.record(), meta.name(), |record| {
+            let function = record.document_meta_function::<A, R>(meta, Some(T::to_typename()));
+            record.meta_method.push(function)
+        });
+        match deprecation {
+            Some((full_name, deprecation)) => {
+                self.cont.add_meta_method(meta, move |lua, this, args| {
+                    warn_deprecated(lua, &full_name, &deprecation)?;
+                    method(lua, this, args)
+                })
+            }
+            None => self.cont.add_meta_method(meta, method),
+        }
     }
     #[inline(always)]
-    fn add_meta_method_mut<A, R, M>(&mut self, meta: MetaMethod, method: M)
+    fn add_meta_method_mut<A, R, M>(&mut self, meta: MetaMethod, mut method: M)
     where
         A: FromLuaMulti + TealMultiValue,
         R: ToLuaMulti + TealMultiValue,
         M: 'static + MaybeSend + FnMut(&Lua, &mut T, A) -> Result<R>,
     {
-        self.copy_method_docs::<A, R>(meta.name(), true);
-        self.cont.add_meta_method_mut(meta, method)
+        let deprecation = document_member(self.record(), meta.name(), |record| {
+            let function = record.document_meta_function::<A, R>(meta, Some(T::to_typename()));
+            record.meta_method_mut.push(function)
+        });
+        match deprecation {
+            Some((full_name, deprecation)) => {
+                self.cont.add_meta_method_mut(meta, move |lua, this, args| {
+                    warn_deprecated(lua, &full_name, &deprecation)?;
+                    method(lua, this, args)
+                })
+            }
+            None => self.cont.add_meta_method_mut(meta, method),
+        }
     }
 
     #[inline(always)]
@@ -237,61 +350,153 @@ where
         R: ToLuaMulti + TealMultiValue,
         F: 'static + MaybeSend + Fn(&Lua, A) -> Result<R>,
     {
-        self.copy_method_docs::<A, R>(meta.name(), false);
-        self.cont.add_meta_function(meta, function)
+        let deprecation = document_member(self.record(), meta.name(), |record| {
+            let function = record.document_meta_function::<A, R>(meta, None);
+            record.meta_function.push(function)
+        });
+        match deprecation {
+            Some((full_name, deprecation)) => {
+                self.cont.add_meta_function(meta, move |lua, args| {
+                    warn_deprecated(lua, &full_name, &deprecation)?;
+                    function(lua, args)
+                })
+            }
+            None => self.cont.add_meta_function(meta, function),
+        }
     }
 
     #[inline(always)]
-    fn add_meta_function_mut<A, R, F>(&mut self, meta: MetaMethod, function: F)
+    fn add_meta_function_mut<A, R, F>(&mut self, meta: MetaMethod, mut function: F)
     where
         A: FromLuaMulti + TealMultiValue,
         R: ToLuaMulti + TealMultiValue,
         F: 'static + MaybeSend + FnMut(&Lua, A) -> Result<R>,
     {
-        self.copy_method_docs::<A, R>(meta.name(), false);
-        self.cont.add_meta_function_mut(meta, function)
+        let deprecation = document_member(self.record(), meta.name(), |record| {
+            let function = record.document_meta_function::<A, R>(meta, None);
+            record.meta_function_mut.push(function)
+        });
+        match deprecation {
+            Some((full_name, deprecation)) => {
+                self.cont.add_meta_function_mut(meta, move |lua, args| {
+                    warn_deprecated(lua, &full_name, &deprecation)?;
+                    function(lua, args)
+                })
+            }
+            None => self.cont.add_meta_function_mut(meta, function),
+        }
     }
 
     fn document(&mut self, documentation: &str) -> &mut Self {
-        self.document(documentation);
+        self.record().document(documentation);
         self
     }
     fn document_type(&mut self, documentation: &str) -> &mut Self {
-        self.type_doc.push_str(documentation);
-        self.type_doc.push('\n');
+        self.record().document_type(documentation);
+        self
+    }
+    fn document_param(&mut self, name: &str, documentation: &str) -> &mut Self {
+        self.record()
+            .next_function_docs()
+            .document_param(name, documentation);
+        self
+    }
+    fn document_return(&mut self, documentation: &str) -> &mut Self {
+        self.record()
+            .next_function_docs()
+            .document_return(documentation);
+        self
+    }
+    fn document_error(&mut self, documentation: &str) -> &mut Self {
+        self.record()
+            .next_function_docs()
+            .document_error(documentation);
+        self
+    }
+    fn document_example(&mut self, code: &str) -> &mut Self {
+        self.record().next_function_docs().document_example(code);
+        self
+    }
+    fn deprecate(&mut self, reason: &str, since: Option<&str>) -> &mut Self {
+        self.record().deprecate(reason, since);
+        self
+    }
+    fn since(&mut self, version: &str) -> &mut Self {
+        self.record().since(version);
+        self
+    }
+    fn stability(&mut self, stability: Stability) -> &mut Self {
+        self.record().stability(stability);
+        self
+    }
+    fn placement(&mut self, placement: Placement) -> &mut Self {
+        self.record().placement(placement);
         self
     }
 
     fn generate_help(&mut self) {
-        let help = self.documentation.clone();
-        let type_doc = self.type_doc.clone();
-        self.add_function("help", move |lua, key: Option<mlua::String>| {
-            let doc = match key {
-                Some(x) => help
-                    .get(x.as_bytes().as_bytes())
-                    .map(|v| v.as_bytes())
-                    .map(ToOwned::to_owned)
-                    .unwrap_or_else(|| {
-                        b"The given key is not found. Use `.help()` to list available pages."
-                            .to_vec()
-                    }),
-                None => {
-                    let mut x = help
-                        .keys()
-                        .map(ToOwned::to_owned)
-                        .flat_map(|mut v| {
-                            v.push_char('\n');
-                            v
-                        })
-                        .collect::<Vec<_>>();
-                    let mut y = (type_doc.clone() + "\n" + "Available pages:\n").into_bytes();
-                    y.append(&mut x);
-                    y
-                }
-            };
+        let record = self.record();
+        record.add_function::<_, Option<String>, String>("help");
+        record.add_function::<_, Option<String>, Vec<HashMap<String, String>>>("help_entries");
+        let help = HelpPages::from_record(record);
+        let entries = help.clone();
+        self.cont
+            .add_function("help", move |_, query: Option<mlua::String>| {
+                let query = query.map(|v| v.to_string_lossy());
+                Ok(help.page(query.as_deref()))
+            });
+        self.cont
+            .add_function("help_entries", move |_, query: Option<mlua::String>| {
+                let query = query.map(|v| v.to_string_lossy());
+                Ok(entries.entries(query.as_deref()))
+            });
+    }
+    #[cfg(feature = "derive")]
+    fn generate_type_info(&mut self) {
+        let record = self.record();
+        //the derive macros can call this outside of `TealData::add_methods`
+        if !record
+            .functions
+            .iter()
+            .any(|v| v.name == crate::type_generator::TYPE_INFO_FUNCTION)
+        {
+            record
+                .add_function::<_, (), RecordGenerator>(crate::type_generator::TYPE_INFO_FUNCTION);
+        }
+        let record = record.clone();
+        self.cont
+            .add_function(crate::type_generator::TYPE_INFO_FUNCTION, move |_, ()| {
+                Ok(record.clone())
+            });
+    }
+
+    fn add_dynamic_index<K, V, F>(&mut self, index: F)
+    where
+        K: FromLua + ToTypename,
+        V: ToLua + ToTypename,
+        F: 'static + MaybeSend + Fn(&Lua, &T, K) -> Result<Option<V>>,
+    {
+        self.record().dynamic_index = Some(MapRepresentation {
+            key: Box::new(K::to_typename()),
+            value: Box::new(V::to_typename()),
+        });
+        self.add_meta_method(MetaMethod::Index, index)
+    }
 
-            lua.create_string(doc)
-        })
+    fn add_constructor<A, R, F>(&mut self, constructor: F)
+    where
+        A: FromLuaMulti + TealMultiValue,
+        R: ToLuaMulti + TealMultiValue,
+        F: 'static + MaybeSend + Fn(&Lua, A) -> Result<R>,
+    {
+        self.add_function("new", constructor);
+        let record = self.record();
+        record.constructor = record.functions.last().cloned();
+        //`UserDataProxy` turns this into `__call`, which gets the proxy as its first argument, so it forwards the rest to `new`
+        self.cont
+            .add_meta_function(PROXY_CALL, |_, (this, args): (AnyUserData, MultiValue)| {
+                this.get::<Function>("new")?.call::<MultiValue>(args)
+            });
     }
 }
 
@@ -301,7 +506,19 @@ where
     Container: UserDataFields<T>,
 {
     fn document(&mut self, documentation: &str) {
-        self.document(documentation)
+        self.record().document(documentation);
+    }
+    fn deprecate(&mut self, reason: &str, since: Option<&str>) {
+        self.record().deprecate(reason, since);
+    }
+    fn since(&mut self, version: &str) {
+        self.record().since(version);
+    }
+    fn stability(&mut self, stability: Stability) {
+        self.record().stability(stability);
+    }
+    fn placement(&mut self, placement: Placement) {
+        self.record().placement(placement);
     }
 
     fn add_field_method_get<S, R, M>(&mut self, name: S, method: M)
@@ -310,8 +527,18 @@ where
         R: mlua::IntoLua + ToTypename,
         M: 'static + MaybeSend + Fn(&Lua, &T) -> mlua::Result<R>,
     {
-        self.copy_field_docs::<R>(name.as_ref());
-        self.cont.add_field_method_get(name, method)
+        let deprecation = document_member(self.record(), name.as_ref(), |record| {
+            record.add_field::<_, R>(name.as_ref())
+        });
+        match deprecation {
+            Some((full_name, deprecation)) => {
+                self.cont.add_field_method_get(name, move |lua, this| {
+                    warn_deprecated(lua, &full_name, &deprecation)?;
+                    method(lua, this)
+                })
+            }
+            None => self.cont.add_field_method_get(name, method),
+        }
     }
 
     fn add_field_method_set<S, A, M>(&mut self, name: S, method: M)
@@ -320,8 +547,20 @@ where
         A: mlua::FromLua + ToTypename,
         M: 'static + MaybeSend + FnMut(&Lua, &mut T, A) -> mlua::Result<()>,
     {
-        self.copy_field_docs::<A>(name.as_ref());
-        self.cont.add_field_method_set(name, method)
+        let deprecation = document_member(self.record(), name.as_ref(), |record| {
+            record.add_field::<_, A>(name.as_ref())
+        });
+        let mut method = method;
+        match deprecation {
+            Some((full_name, deprecation)) => {
+                self.cont
+                    .add_field_method_set(name, move |lua, this, value| {
+                        warn_deprecated(lua, &full_name, &deprecation)?;
+                        method(lua, this, value)
+                    })
+            }
+            None => self.cont.add_field_method_set(name, method),
+        }
     }
 
     fn add_field_function_get<S, R, F>(&mut self, name: S, function: F)
@@ -330,8 +569,18 @@ where
         R: mlua::IntoLua + ToTypename,
         F: 'static + MaybeSend + Fn(&Lua, mlua::AnyUserData) -> mlua::Result<R>,
     {
-        self.copy_field_docs::<R>(name.as_ref());
-        self.cont.add_field_function_get(name, function)
+        let deprecation = document_member(self.record(), name.as_ref(), |record| {
+            record.add_static_field::<_, R>(name.as_ref())
+        });
+        match deprecation {
+            Some((full_name, deprecation)) => {
+                self.cont.add_field_function_get(name, move |lua, this| {
+                    warn_deprecated(lua, &full_name, &deprecation)?;
+                    function(lua, this)
+                })
+            }
+            None => self.cont.add_field_function_get(name, function),
+        }
     }
 
     fn add_field_function_set<S, A, F>(&mut self, name: S, function: F)
@@ -340,8 +589,41 @@ where
         A: mlua::FromLua + ToTypename,
         F: 'static + MaybeSend + FnMut(&Lua, mlua::AnyUserData, A) -> mlua::Result<()>,
     {
-        self.copy_field_docs::<A>(name.as_ref());
-        self.cont.add_field_function_set(name, function)
+        let deprecation = document_member(self.record(), name.as_ref(), |record| {
+            record.add_static_field::<_, A>(name.as_ref())
+        });
+        let mut function = function;
+        match deprecation {
+            Some((full_name, deprecation)) => {
+                self.cont
+                    .add_field_function_set(name, move |lua, this, value| {
+                        warn_deprecated(lua, &full_name, &deprecation)?;
+                        function(lua, this, value)
+                    })
+            }
+            None => self.cont.add_field_function_set(name, function),
+        }
+    }
+
+    fn add_constant<S, V>(&mut self, name: S, value: V)
+    where
+        S: AsRef<str> + ToString,
+        V: 'static + MaybeSend + Clone + mlua::IntoLua + ToTypename + crate::ToLuaLiteral,
+    {
+        let deprecation = document_member(self.record(), name.as_ref(), |record| {
+            record.document_constant(name.as_ref(), &value)
+        });
+        match deprecation {
+            Some((full_name, deprecation)) => {
+                self.cont.add_field_function_get(name, move |lua, _| {
+                    warn_deprecated(lua, &full_name, &deprecation)?;
+                    Ok(value.clone())
+                })
+            }
+            None => self
+                .cont
+                .add_field_function_get(name, move |_, _| Ok(value.clone())),
+        }
     }
 
     fn add_meta_field_with<R, F>(&mut self, meta: MetaMethod, f: F)
@@ -349,7 +631,8 @@ where
         F: 'static + MaybeSend + Fn(&Lua) -> mlua::Result<R>,
         R: mlua::IntoLua + ToTypename,
     {
-        self.copy_field_docs::<R>(meta.name());
+        //meta fields are read once, when the metatable gets created, so there is nothing to warn about
+        self.record().document_meta_field::<R>(meta);
         self.cont.add_meta_field_with(meta, f)
     }
 }
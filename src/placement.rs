@@ -0,0 +1,43 @@
+use std::fmt::Display;
+
+///On which type a static member gets documented.
+///
+///Static members, like functions and fields added with `add_field_function_get`, can be reached both through a value
+///and through its [UserDataProxy](crate::mlu::UserDataProxy). By default they get documented on both.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[cfg_attr(
+    all(feature = "mlua", feature = "derive"),
+    derive(crate::mlu::FromToLua, crate::ToTypename)
+)]
+#[cfg_attr(
+    all(feature = "mlua", feature = "derive"),
+    tealr(tealr_name = crate)
+)]
+pub enum Placement {
+    ///Documented on both the type itself and its proxy
+    #[default]
+    Both,
+    ///Only documented on the type itself
+    Instance,
+    ///Only documented on the proxy
+    Proxy,
+}
+
+impl Placement {
+    ///if a member with this placement gets documented on the given side
+    pub fn is_shown_on(self, side: Placement) -> bool {
+        self == Placement::Both || self == side
+    }
+}
+
+impl Display for Placement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Placement::Both => "both",
+            Placement::Instance => "instance",
+            Placement::Proxy => "proxy",
+        })
+    }
+}
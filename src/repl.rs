@@ -0,0 +1,384 @@
+use mlua::{Lua, MultiValue, Result, Value};
+
+use crate::{
+    mlu::help::{HelpKind, HelpPages},
+    type_to_string, GlobalInstance, RecordGenerator, Type, TypeGenerator, TypeWalker,
+};
+
+///What kind of item a [Completion] refers to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CompletionKind {
+    ///a global, or a table that other globals are nested in
+    Global,
+    ///a field
+    Field,
+    ///a method, called using `:`
+    Method,
+    ///a function, called using `.`
+    Function,
+}
+
+///A single candidate offered by [Repl::complete], also used by [Repl::describe]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Completion {
+    ///the name of the item
+    pub name: String,
+    ///what kind of item it is
+    pub kind: CompletionKind,
+    ///the type of the item, as teal would write it
+    pub signature: String,
+    ///the documentation of the item
+    pub doc: String,
+}
+
+///The candidates to complete a line with
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Completions {
+    ///the byte offset in the line where the word that is being completed starts.
+    ///
+    ///A candidate replaces everything from this offset until the end of the line
+    pub start: usize,
+    ///every candidate that starts with the word that is being completed, sorted by name
+    pub candidates: Vec<Completion>,
+}
+
+///A single step in an expression like `game.player:get_position().x`
+#[derive(Clone, Copy, Debug)]
+struct Access<'a> {
+    ///the `.` or `:` in front of the name, `None` for the first name of the expression
+    separator: Option<char>,
+    name: &'a str,
+    is_called: bool,
+}
+
+///What the part of an expression that has been looked at so far refers to
+#[derive(Clone, Debug)]
+enum Resolved<'a> {
+    ///a table that global instances are nested in, the globals themselves if empty
+    Namespace(Vec<&'a str>),
+    Type(Type),
+}
+
+///A lua REPL that uses the types collected by a [TypeWalker] to complete and describe expressions.
+///
+///It doesn't read or print anything itself, so it can be hosted in any UI.
+///```
+///# use tealr::{repl::Repl, TypeWalker};
+///# use tealr::mlu::mlua::Lua;
+///let repl = Repl::new(Lua::new(), TypeWalker::new());
+///assert_eq!(repl.eval("1 + 2").unwrap(), "3");
+///let completions = repl.complete("pri");
+///assert_eq!(completions.start, 0);
+///assert!(completions.candidates.iter().any(|v| v.name == "print"));
+///```
+pub struct Repl {
+    lua: Lua,
+    walker: TypeWalker,
+}
+
+impl Repl {
+    ///creates a REPL that runs code in the given lua state and completes it using the given types
+    pub fn new(lua: Lua, walker: TypeWalker) -> Self {
+        Self { lua, walker }
+    }
+    ///the lua state that the code is run in
+    pub fn lua(&self) -> &Lua {
+        &self.lua
+    }
+    ///the types that are used to complete code
+    pub fn walker(&self) -> &TypeWalker {
+        &self.walker
+    }
+    ///runs a line of code and returns the values it resulted in, separated by tabs.
+    ///
+    ///Like the standalone lua interpreter, the line is first tried as an expression and only then as a statement.
+    pub fn eval(&self, line: &str) -> Result<String> {
+        let values = match self
+            .lua
+            .load(format!("return {}", line))
+            .set_name("=repl")
+            .into_function()
+        {
+            Ok(function) => function.call::<MultiValue>(())?,
+            Err(_) => self
+                .lua
+                .load(line)
+                .set_name("=repl")
+                .call::<MultiValue>(())?,
+        };
+        Ok(values
+            .iter()
+            .map(Value::to_string)
+            .collect::<Result<Vec<_>>>()?
+            .join("\t"))
+    }
+    ///gives the candidates to complete the expression at the end of the line with.
+    ///
+    ///Globals are always offered, fields and methods only if the type of the expression is known
+    pub fn complete(&self, line: &str) -> Completions {
+        let start = expression_start(line);
+        let accesses = split_expression(&line[start..]);
+        let (last, path) = accesses
+            .split_last()
+            .expect("an expression always accesses at least one name");
+        if last.is_called {
+            return Completions {
+                start: line.len(),
+                candidates: Vec::new(),
+            };
+        }
+        let candidates = match self.resolve(path) {
+            Some(resolved) => self
+                .members(&resolved, last.separator)
+                .into_iter()
+                .filter(|v| v.name.starts_with(last.name))
+                .collect(),
+            None => Vec::new(),
+        };
+        Completions {
+            start: line.len() - last.name.len(),
+            candidates,
+        }
+    }
+    ///gives the signature and documentation of what the expression refers to, if it is known.
+    ///```
+    ///# use tealr::{repl::Repl, TypeWalker};
+    ///# use tealr::mlu::mlua::Lua;
+    ///let repl = Repl::new(Lua::new(), TypeWalker::new());
+    ///assert_eq!(repl.describe("print").unwrap().signature, "function");
+    ///```
+    pub fn describe(&self, expression: &str) -> Option<Completion> {
+        let accesses = split_expression(expression.trim());
+        let (last, path) = accesses.split_last()?;
+        let resolved = self.resolve(path)?;
+        self.members(&resolved, last.separator)
+            .into_iter()
+            .find(|v| v.name == last.name)
+    }
+
+    fn resolve<'a>(&'a self, accesses: &[Access<'a>]) -> Option<Resolved<'a>> {
+        let mut resolved = Resolved::Namespace(Vec::new());
+        for access in accesses {
+            resolved = match resolved {
+                Resolved::Namespace(mut path) => {
+                    match self.globals().find(|v| {
+                        is_nested_in(&v.namespace, &path)
+                            && v.namespace.len() == path.len()
+                            && v.name == access.name
+                    }) {
+                        Some(global) => Resolved::Type(global.ty.clone()),
+                        None => {
+                            path.push(access.name);
+                            if !self.globals().any(|v| is_nested_in(&v.namespace, &path)) {
+                                return None;
+                            }
+                            Resolved::Namespace(path)
+                        }
+                    }
+                }
+                Resolved::Type(ty) => {
+                    let record = self.record_of(&ty)?;
+                    Resolved::Type(member_type(record, access)?)
+                }
+            };
+            if access.is_called {
+                resolved = match resolved {
+                    Resolved::Type(Type::Function(x)) => {
+                        Resolved::Type(x.returns.into_iter().next()?)
+                    }
+                    Resolved::Type(_) => return None,
+                    x => x,
+                }
+            }
+        }
+        Some(resolved)
+    }
+    fn globals(&self) -> impl Iterator<Item = &GlobalInstance> {
+        self.walker
+            .global_instances_off
+            .iter()
+            .chain(self.walker.modules.iter().flat_map(|v| v.instances.iter()))
+    }
+    fn record_of(&self, ty: &Type) -> Option<&RecordGenerator> {
+        let name = match ty {
+            Type::Single(x) => &x.name,
+            _ => return None,
+        };
+        self.walker.iter().find_map(|v| match v {
+            TypeGenerator::Record(x) => match &x.ty {
+                Type::Single(y) if y.name == *name => Some(x.as_ref()),
+                _ => None,
+            },
+            TypeGenerator::Enum(_) | TypeGenerator::TaggedUnion(_) => None,
+        })
+    }
+    fn members(&self, resolved: &Resolved, separator: Option<char>) -> Vec<Completion> {
+        let mut members = match resolved {
+            Resolved::Namespace(path) => self.globals_in(path),
+            Resolved::Type(ty) => match self.record_of(ty) {
+                Some(record) => HelpPages::from_record(record)
+                    .iter()
+                    .filter_map(|entry| {
+                        let kind = match (entry.kind, separator) {
+                            (HelpKind::Field | HelpKind::StaticField, Some('.')) => {
+                                CompletionKind::Field
+                            }
+                            (HelpKind::Function | HelpKind::Constructor, Some('.')) => {
+                                CompletionKind::Function
+                            }
+                            (HelpKind::Method, Some(':')) => CompletionKind::Method,
+                            _ => return None,
+                        };
+                        Some(Completion {
+                            name: entry.name.clone(),
+                            kind,
+                            signature: entry.signature.clone(),
+                            doc: entry.doc.clone(),
+                        })
+                    })
+                    .collect(),
+                None => Vec::new(),
+            },
+        };
+        members.sort_by(|a, b| a.name.cmp(&b.name));
+        members.dedup_by(|a, b| a.name == b.name);
+        members
+    }
+    fn globals_in(&self, path: &[&str]) -> Vec<Completion> {
+        let mut globals = Vec::new();
+        for global in self.globals() {
+            if !is_nested_in(&global.namespace, path) {
+                continue;
+            }
+            if global.namespace.len() == path.len() {
+                globals.push(Completion {
+                    name: global.name.clone(),
+                    kind: match global.ty {
+                        Type::Function(_) => CompletionKind::Function,
+                        _ => CompletionKind::Global,
+                    },
+                    signature: type_to_string(&global.ty, false),
+                    doc: global.doc.clone(),
+                });
+            } else {
+                globals.push(Completion {
+                    name: global.namespace[path.len()].clone(),
+                    kind: CompletionKind::Global,
+                    signature: "table".to_owned(),
+                    doc: String::new(),
+                });
+            }
+        }
+        //globals that aren't documented, like the standard library, can still be completed by name
+        if path.is_empty() {
+            for (key, value) in self
+                .lua
+                .globals()
+                .pairs::<Value, Value>()
+                .filter_map(std::result::Result::ok)
+            {
+                if let Value::String(key) = key {
+                    globals.push(Completion {
+                        name: key.to_string_lossy(),
+                        kind: CompletionKind::Global,
+                        signature: value.type_name().to_owned(),
+                        doc: String::new(),
+                    });
+                }
+            }
+        }
+        globals
+    }
+}
+
+fn member_type(record: &RecordGenerator, access: &Access) -> Option<Type> {
+    if access.separator == Some(':') {
+        return record
+            .methods
+            .iter()
+            .chain(&record.mut_methods)
+            .find(|v| v.name == access.name)
+            .map(|v| v.into_type());
+    }
+    let field = record
+        .fields
+        .iter()
+        .chain(&record.field_functions)
+        .find(|v| v.name == access.name);
+    if let Some(field) = field {
+        return Some(field.ty.clone());
+    }
+    record
+        .functions
+        .iter()
+        .chain(&record.mut_functions)
+        .find(|v| v.name == access.name)
+        .map(|v| v.into_type())
+}
+
+fn is_nested_in(namespace: &[String], path: &[&str]) -> bool {
+    namespace.len() >= path.len() && namespace.iter().zip(path).all(|(a, b)| a == b)
+}
+
+///finds where the expression at the end of the line starts
+fn expression_start(line: &str) -> usize {
+    let bytes = line.as_bytes();
+    let mut depth = 0;
+    let mut start = bytes.len();
+    while start > 0 {
+        match bytes[start - 1] {
+            b')' => depth += 1,
+            b'(' if depth > 0 => depth -= 1,
+            _ if depth > 0 => (),
+            c if c.is_ascii_alphanumeric() || c == b'_' || c == b'.' || c == b':' => (),
+            _ => break,
+        }
+        start -= 1;
+    }
+    start
+}
+
+///splits an expression like `game.player:get_position().x` into the names it accesses
+fn split_expression(expression: &str) -> Vec<Access<'_>> {
+    let bytes = expression.as_bytes();
+    let mut accesses = Vec::new();
+    let mut separator = None;
+    let mut position = 0;
+    loop {
+        let start = position;
+        while position < bytes.len()
+            && (bytes[position].is_ascii_alphanumeric() || bytes[position] == b'_')
+        {
+            position += 1;
+        }
+        let name = &expression[start..position];
+        let mut is_called = false;
+        if position < bytes.len() && bytes[position] == b'(' {
+            is_called = true;
+            let mut depth = 0;
+            while position < bytes.len() {
+                match bytes[position] {
+                    b'(' => depth += 1,
+                    b')' => depth -= 1,
+                    _ => (),
+                }
+                position += 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+        }
+        accesses.push(Access {
+            separator,
+            name,
+            is_called,
+        });
+        match bytes.get(position) {
+            Some(b'.') => separator = Some('.'),
+            Some(b':') => separator = Some(':'),
+            _ => break,
+        }
+        position += 1;
+    }
+    accesses
+}
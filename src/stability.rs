@@ -0,0 +1,45 @@
+use std::fmt::Display;
+
+///How stable a type or member is, used to decide if it should be part of the public definitions.
+///
+///The variants are ordered from most to least stable, so `Stability::Stable < Stability::Internal`
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[cfg_attr(
+    all(feature = "mlua", feature = "derive"),
+    derive(crate::mlu::FromToLua, crate::ToTypename)
+)]
+#[cfg_attr(
+    all(feature = "mlua", feature = "derive"),
+    tealr(tealr_name = crate)
+)]
+pub enum Stability {
+    ///Safe to use, it won't change without a major version bump
+    #[default]
+    Stable,
+    ///Can still change or be removed between versions
+    Experimental,
+    ///Only meant to be used by the application itself
+    Internal,
+}
+
+impl Display for Stability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Stability::Stable => "stable",
+            Stability::Experimental => "experimental",
+            Stability::Internal => "internal",
+        })
+    }
+}
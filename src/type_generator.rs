@@ -17,7 +17,11 @@ use crate::mlu::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{exported_function::ExportedFunction, type_to_string, ToTypename, Type};
+use crate::{
+    exported_function::{ExportedFunction, FunctionDocs},
+    type_to_string, Deprecation, KindOfType, MapRepresentation, Placement, Stability, ToLuaLiteral,
+    ToTypename, Type,
+};
 
 use crate::TealMultiValue;
 
@@ -107,6 +111,10 @@ impl From<String> for NameContainer {
     }
 }
 
+///name of the function created by [TealDataMethods::generate_type_info](crate::mlu::TealDataMethods::generate_type_info)
+#[cfg(all(feature = "mlua", feature = "derive"))]
+pub(crate) const TYPE_INFO_FUNCTION: &str = "__tealr_type";
+
 #[allow(dead_code)]
 pub(crate) fn get_method_data<A: TealMultiValue, R: TealMultiValue, S: ToString + AsRef<str>>(
     name: S,
@@ -140,6 +148,8 @@ pub enum TypeGenerator {
     ),
     ///the type should be represented as an enum
     Enum(EnumGenerator),
+    ///the type should be represented as a union of records, told apart by the value of a tag field
+    TaggedUnion(TaggedUnionGenerator),
 }
 
 impl TypeGenerator {
@@ -148,20 +158,21 @@ impl TypeGenerator {
         match self {
             TypeGenerator::Record(record_generator) => &record_generator.ty,
             TypeGenerator::Enum(enum_generator) => &enum_generator.ty,
+            TypeGenerator::TaggedUnion(tagged_union) => &tagged_union.ty,
         }
     }
     /// returns true if `self` is an TypeGenerator::Record(x) and x.should_be_inlined is true
     pub fn is_inlined(&self) -> bool {
         match self {
             TypeGenerator::Record(record_generator) => record_generator.should_be_inlined,
-            TypeGenerator::Enum(_) => false,
+            TypeGenerator::Enum(_) | TypeGenerator::TaggedUnion(_) => false,
         }
     }
     ///returns the RecordGenerator if self is `TypeGenerator::Record(x)` otherwise returns None
     pub fn record(&self) -> Option<&RecordGenerator> {
         match self {
             TypeGenerator::Record(record_generator) => Some(record_generator),
-            TypeGenerator::Enum(_) => None,
+            TypeGenerator::Enum(_) | TypeGenerator::TaggedUnion(_) => None,
         }
     }
 }
@@ -192,6 +203,12 @@ pub struct EnumGenerator {
     pub variants: Vec<NameContainer>,
     ///documentation for this enum
     pub type_doc: String,
+    ///documentation for the variants of this enum
+    #[serde(default)]
+    pub variant_docs: HashMap<NameContainer, String>,
+    ///how stable this enum is
+    #[serde(default)]
+    pub stability: Stability,
 }
 impl From<EnumGenerator> for TypeGenerator {
     fn from(a: EnumGenerator) -> Self {
@@ -206,6 +223,8 @@ impl EnumGenerator {
             name: type_to_string(&A::to_typename(), false),
             variants: Default::default(),
             type_doc: Default::default(),
+            variant_docs: Default::default(),
+            stability: Default::default(),
         }
     }
     ///Add type level documentation to this enum
@@ -215,6 +234,372 @@ impl EnumGenerator {
         self.type_doc.push('\n');
         self
     }
+    ///Add documentation to a variant of this enum
+    pub fn document_variant(&mut self, variant: &str, documentation: &str) -> &mut Self {
+        let doc = self
+            .variant_docs
+            .entry(variant.as_bytes().to_vec().into())
+            .or_default();
+        doc.push_str(documentation);
+        doc.push('\n');
+        self
+    }
+}
+
+///How the variants of an enum are stored in plain lua values, similar to the enum representations of serde.
+///
+///The examples show how `Shape::Circle { radius: 2 }` is stored.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(
+    all(feature = "mlua", feature = "derive"),
+    derive(crate::mlu::FromToLua, crate::ToTypename)
+)]
+#[cfg_attr(
+    all(feature = "mlua", feature = "derive"),
+    tealr(tealr_name = crate)
+)]
+pub enum EnumRepresentation {
+    ///`{ kind = "Circle", radius = 2 }`, holds the name of the tag field
+    InternallyTagged(String),
+    ///`{ Circle = { radius = 2 } }`. Variants without a value are stored as just their name
+    ExternallyTagged,
+    ///`{ kind = "Circle", content = { radius = 2 } }`, holds the name of the tag and of the content field
+    AdjacentlyTagged(String, String),
+    ///`{ radius = 2 }`. Variants without a value are stored as `nil` and the first variant that fits a value is used
+    Untagged,
+}
+
+///contains all the information needed to create a union out of the variants of an enum that is stored in plain lua values,
+///like `{ kind = "circle", radius = 2 }` and `{ kind = "rect", w = 1, h = 2 }`.
+///
+///Every variant with fields becomes its own record and the type itself becomes a union of the variants.
+///How the variants are told apart is decided by the [EnumRepresentation].
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(
+    all(feature = "mlua", feature = "derive"),
+    derive(crate::mlu::FromToLua, crate::ToTypename)
+)]
+#[cfg_attr(
+    all(feature = "mlua", feature = "derive"),
+    tealr(tealr_name = crate)
+)]
+
+pub struct TaggedUnionGenerator {
+    ///the type of this union
+    pub ty: Type,
+    ///the name of this union
+    pub name: String,
+    ///how the variants are stored and told apart
+    pub representation: EnumRepresentation,
+    ///the variants that make up this union
+    pub variants: Vec<TaggedVariant>,
+    ///documentation for this union
+    pub type_doc: String,
+    ///how stable this union is
+    #[serde(default)]
+    pub stability: Stability,
+}
+impl From<TaggedUnionGenerator> for TypeGenerator {
+    fn from(a: TaggedUnionGenerator) -> Self {
+        TypeGenerator::TaggedUnion(a)
+    }
+}
+impl TaggedUnionGenerator {
+    ///creates a new TaggedUnionGenerator, where `tag` is the name of the field that decides the variant
+    pub fn new<A: ToTypename>(tag: &str) -> Self {
+        Self::with_representation::<A>(EnumRepresentation::InternallyTagged(tag.to_owned()))
+    }
+    ///creates a new TaggedUnionGenerator, for an enum stored the way `representation` describes
+    pub fn with_representation<A: ToTypename>(representation: EnumRepresentation) -> Self {
+        Self {
+            ty: A::to_typename(),
+            name: type_to_string(&A::to_typename(), false),
+            representation,
+            variants: Default::default(),
+            type_doc: Default::default(),
+            stability: Default::default(),
+        }
+    }
+    ///Add type level documentation to this union
+    pub fn document_type(&mut self, documentation: &str) -> &mut Self {
+        self.type_doc.push_str(documentation);
+        self.type_doc.push('\n');
+        self.type_doc.push('\n');
+        self
+    }
+    ///Add a variant, whose record is named after the union and `variant` and that is identified by `tag_value`
+    pub fn add_variant(&mut self, variant: &str, tag_value: &str) -> &mut TaggedVariant {
+        self.variants.push(TaggedVariant {
+            name: format!("{}{}", self.name, variant).into_bytes().into(),
+            tag_value: tag_value.to_owned(),
+            fields: Default::default(),
+            value: None,
+            doc: Default::default(),
+            documentation: Default::default(),
+        });
+        self.variants.last_mut().unwrap()
+    }
+    ///renders the union the way teal declares it, as a record for every variant with fields and a type alias combining them.
+    ///
+    ///Tagged variants get a `where` clause so teal can tell them apart.
+    ///```
+    ///# use tealr::{Field, TaggedUnionGenerator, ToTypename};
+    ///# #[derive(ToTypename)]
+    ///# struct Shape;
+    ///let mut union = TaggedUnionGenerator::new::<Shape>("kind");
+    ///union.add_variant("Circle", "circle").fields.push(Field::new::<f64>("radius"));
+    ///union.add_variant("Empty", "empty");
+    ///assert_eq!(
+    ///    union.to_teal(),
+    ///    "record ShapeCircle where self.kind == \"circle\"\n   kind: string\n   radius: number\nend\n\
+    ///    record ShapeEmpty where self.kind == \"empty\"\n   kind: string\nend\n\
+    ///    type Shape = ShapeCircle | ShapeEmpty\n"
+    ///);
+    ///```
+    pub fn to_teal(&self) -> String {
+        let mut teal = String::new();
+        let mut members = Vec::new();
+        for variant in &self.variants {
+            let (clause, tag, payload) = match &self.representation {
+                EnumRepresentation::InternallyTagged(tag) => (
+                    Some(format!(
+                        "self.{} == {}",
+                        tag,
+                        variant.tag_value.to_lua_literal()
+                    )),
+                    Some(tag.as_str()),
+                    None,
+                ),
+                EnumRepresentation::AdjacentlyTagged(tag, content) => (
+                    Some(format!(
+                        "self.{} == {}",
+                        tag,
+                        variant.tag_value.to_lua_literal()
+                    )),
+                    Some(tag.as_str()),
+                    Some(content.as_str()),
+                ),
+                EnumRepresentation::ExternallyTagged if variant.is_unit() => {
+                    if !members.contains(&"string".to_owned()) {
+                        members.push("string".to_owned());
+                    }
+                    continue;
+                }
+                EnumRepresentation::ExternallyTagged => (
+                    Some(format!("self.{} ~= nil", variant.tag_value)),
+                    None,
+                    Some(variant.tag_value.as_str()),
+                ),
+                EnumRepresentation::Untagged => match &variant.value {
+                    Some(ty) => {
+                        members.push(type_to_string(ty, false));
+                        continue;
+                    }
+                    None if variant.fields.is_empty() => continue,
+                    None => (None, None, None),
+                },
+            };
+            match clause {
+                Some(clause) => {
+                    teal.push_str(&format!("record {} where {}\n", variant.name, clause))
+                }
+                None => teal.push_str(&format!("record {}\n", variant.name)),
+            }
+            let fields = variant
+                .fields
+                .iter()
+                .map(|field| format!("{}: {}", field.name, type_to_string(&field.ty, false)));
+            match payload {
+                Some(payload) if !variant.fields.is_empty() => {
+                    teal.push_str("   record Content\n");
+                    for field in fields {
+                        teal.push_str(&format!("      {}\n", field));
+                    }
+                    teal.push_str("   end\n");
+                    if let Some(tag) = tag {
+                        teal.push_str(&format!("   {}: string\n", tag));
+                    }
+                    teal.push_str(&format!("   {}: Content\n", payload));
+                }
+                Some(payload) => {
+                    if let Some(tag) = tag {
+                        teal.push_str(&format!("   {}: string\n", tag));
+                    }
+                    if let Some(ty) = &variant.value {
+                        teal.push_str(&format!("   {}: {}\n", payload, type_to_string(ty, false)));
+                    }
+                }
+                None => {
+                    if let Some(tag) = tag {
+                        teal.push_str(&format!("   {}: string\n", tag));
+                    }
+                    for field in fields {
+                        teal.push_str(&format!("   {}\n", field));
+                    }
+                }
+            }
+            teal.push_str("end\n");
+            members.push(variant.name.to_string());
+        }
+        teal.push_str(&format!("type {} = {}\n", self.name, members.join(" | ")));
+        teal
+    }
+    ///renders the union the way luau declares it, as a table type for every variant with fields and a type alias combining them.
+    ///
+    ///Tags are typed as string singletons, which luau uses to tell the variants apart.
+    ///```
+    ///# use tealr::{Field, TaggedUnionGenerator, ToTypename};
+    ///# #[derive(ToTypename)]
+    ///# struct Shape;
+    ///let mut union = TaggedUnionGenerator::new::<Shape>("kind");
+    ///union.add_variant("Circle", "circle").fields.push(Field::new::<i64>("radius"));
+    ///union.add_variant("Empty", "empty");
+    ///assert_eq!(
+    ///    union.to_luau(),
+    ///    "type ShapeCircle = { kind: \"circle\", radius: number }\n\
+    ///    type ShapeEmpty = { kind: \"empty\" }\n\
+    ///    type Shape = ShapeCircle | ShapeEmpty\n"
+    ///);
+    ///```
+    pub fn to_luau(&self) -> String {
+        let mut luau = String::new();
+        let mut members = Vec::new();
+        for variant in &self.variants {
+            let fields = variant
+                .fields
+                .iter()
+                .map(|field| (field.name.to_string(), luau_type(&field.ty)))
+                .collect::<Vec<_>>();
+            let content = match &variant.value {
+                Some(ty) => luau_type(ty),
+                None => luau_table(&fields),
+            };
+            let tag = variant.tag_value.to_lua_literal();
+            let table = match &self.representation {
+                EnumRepresentation::InternallyTagged(tag_field) => {
+                    let mut fields = fields.clone();
+                    fields.insert(0, (tag_field.clone(), tag));
+                    luau_table(&fields)
+                }
+                EnumRepresentation::AdjacentlyTagged(tag_field, content_field) => {
+                    let mut fields = vec![(tag_field.clone(), tag)];
+                    if !variant.is_unit() {
+                        fields.push((content_field.clone(), content));
+                    }
+                    luau_table(&fields)
+                }
+                EnumRepresentation::ExternallyTagged if variant.is_unit() => {
+                    members.push(tag);
+                    continue;
+                }
+                EnumRepresentation::ExternallyTagged => {
+                    luau_table(&[(variant.tag_value.clone(), content)])
+                }
+                EnumRepresentation::Untagged if variant.value.is_some() => {
+                    members.push(content);
+                    continue;
+                }
+                EnumRepresentation::Untagged if variant.is_unit() => continue,
+                EnumRepresentation::Untagged => content,
+            };
+            luau.push_str(&format!("type {} = {}\n", variant.name, table));
+            members.push(variant.name.to_string());
+        }
+        luau.push_str(&format!("type {} = {}\n", self.name, members.join(" | ")));
+        luau
+    }
+}
+
+///renders a table type for luau, quoting the keys that aren't valid names
+fn luau_table(fields: &[(String, String)]) -> String {
+    if fields.is_empty() {
+        return "{}".to_owned();
+    }
+    let fields = fields
+        .iter()
+        .map(|(name, ty)| {
+            let is_name = name.chars().next().is_some_and(|c| !c.is_ascii_digit())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if is_name {
+                format!("{}: {}", name, ty)
+            } else {
+                format!("[{}]: {}", name.to_lua_literal(), ty)
+            }
+        })
+        .collect::<Vec<_>>();
+    format!("{{ {} }}", fields.join(", "))
+}
+
+///renders a type the way luau writes it
+fn luau_type(ty: &Type) -> String {
+    let list = |types: &[Type]| types.iter().map(luau_type).collect::<Vec<_>>().join(", ");
+    match ty {
+        Type::Single(x) if x.kind == KindOfType::Builtin && x.name.0 == "integer" => {
+            "number".to_owned()
+        }
+        Type::Single(x) if x.generics.is_empty() => x.name.to_string(),
+        Type::Single(x) => format!("{}<{}>", x.name, list(&x.generics)),
+        Type::Array(x) => format!("{{{}}}", luau_type(x)),
+        Type::Map(x) => format!("{{[{}]: {}}}", luau_type(&x.key), luau_type(&x.value)),
+        Type::Or(x) => format!(
+            "({})",
+            x.iter().map(luau_type).collect::<Vec<_>>().join(" | ")
+        ),
+        Type::Tuple(x) => format!("({})", list(x)),
+        Type::Variadic(x) => format!("...{}", luau_type(x)),
+        Type::Function(x) => {
+            let params = x.params.iter().map(|v| v.ty.clone()).collect::<Vec<_>>();
+            format!("({}) -> ({})", list(&params), list(&x.returns))
+        }
+    }
+}
+
+///A single record of a [TaggedUnionGenerator]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(
+    all(feature = "mlua", feature = "derive"),
+    derive(crate::mlu::FromToLua, crate::ToTypename)
+)]
+#[cfg_attr(
+    all(feature = "mlua", feature = "derive"),
+    tealr(tealr_name = crate)
+)]
+
+pub struct TaggedVariant {
+    ///the name of the record of this variant
+    pub name: NameContainer,
+    ///the value the tag field has for this variant
+    pub tag_value: String,
+    ///the fields of this variant, not including the tag field
+    pub fields: Vec<Field>,
+    ///the type of the value of this variant, if it holds a single unnamed value instead of fields
+    pub value: Option<Type>,
+    ///documentation for this variant
+    pub doc: String,
+    ///documentation for the fields of this variant
+    pub documentation: HashMap<NameContainer, String>,
+}
+impl TaggedVariant {
+    ///returns true if this variant holds neither fields nor a value
+    pub fn is_unit(&self) -> bool {
+        self.fields.is_empty() && self.value.is_none()
+    }
+    ///Add documentation to this variant
+    pub fn document(&mut self, documentation: &str) -> &mut Self {
+        self.doc.push_str(documentation);
+        self.doc.push('\n');
+        self
+    }
+    ///Add documentation to a field of this variant
+    pub fn document_field(&mut self, field: &str, documentation: &str) -> &mut Self {
+        let doc = self
+            .documentation
+            .entry(field.as_bytes().to_vec().into())
+            .or_default();
+        doc.push_str(documentation);
+        doc.push('\n');
+        self
+    }
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
@@ -234,11 +619,34 @@ pub struct Field {
 
     /// the type of the field
     pub ty: Type,
+    /// the value of the field as lua code, if it is a constant
+    #[serde(default)]
+    pub value: Option<String>,
+    /// set if this field is deprecated
+    #[serde(default)]
+    pub deprecated: Option<Deprecation>,
+    /// the version in which this field got added
+    #[serde(default)]
+    pub since: Option<String>,
+    /// how stable this field is
+    #[serde(default)]
+    pub stability: Stability,
+    /// whether a static field is documented on the type, its proxy or both
+    #[serde(default)]
+    pub placement: Placement,
 }
 
 impl From<(NameContainer, Type)> for Field {
     fn from((name, ty): (NameContainer, Type)) -> Self {
-        Self { name, ty }
+        Self {
+            name,
+            ty,
+            value: None,
+            deprecated: None,
+            since: None,
+            stability: Stability::Stable,
+            placement: Placement::Both,
+        }
     }
 }
 impl Field {
@@ -268,8 +676,13 @@ pub struct RecordGenerator {
     pub ty: Type,
     ///The exposed fields and their types
     pub fields: Vec<Field>,
-    ///The exposed static fields and their types
+    ///The exposed meta fields, added with `add_meta_field_with`, and their types
     pub static_fields: Vec<Field>,
+    ///The fields added with `add_field_function_get` and `add_field_function_set`.
+    ///
+    ///They don't depend on a value, so they can be reached through the proxy of the type as well.
+    #[serde(default)]
+    pub field_functions: Vec<Field>,
     ///exported methods
     pub methods: Vec<ExportedFunction>,
     ///exported methods that mutate something
@@ -286,12 +699,42 @@ pub struct RecordGenerator {
     pub meta_function: Vec<ExportedFunction>,
     ///exported meta functions that mutate something
     pub meta_function_mut: Vec<ExportedFunction>,
+    ///the key and value type of the `__index` fallback, if the type can be indexed with keys that aren't fields
+    #[serde(default)]
+    pub dynamic_index: Option<MapRepresentation>,
+    ///the function registered with `add_constructor`, also exposed as `new` and through `__call`
+    #[serde(default)]
+    pub constructor: Option<ExportedFunction>,
     ///registered documentation
     pub documentation: HashMap<NameContainer, String>,
     ///documentation for this type itself
     pub type_doc: String,
+    ///set if this type itself is deprecated
+    #[serde(default)]
+    pub deprecated: Option<Deprecation>,
+    ///the version in which this type got added
+    #[serde(default)]
+    pub since: Option<String>,
+    ///how stable this type is
+    #[serde(default)]
+    pub stability: Stability,
     #[doc(hidden)]
     pub next_docs: Option<String>,
+    #[doc(hidden)]
+    #[serde(default)]
+    pub next_function_docs: Option<FunctionDocs>,
+    #[doc(hidden)]
+    #[serde(default)]
+    pub next_deprecation: Option<Deprecation>,
+    #[doc(hidden)]
+    #[serde(default)]
+    pub next_since: Option<String>,
+    #[doc(hidden)]
+    #[serde(default)]
+    pub next_stability: Option<Stability>,
+    #[doc(hidden)]
+    #[serde(default)]
+    pub next_placement: Option<Placement>,
     ///if this type needs to get a `.help()` function
     pub should_generate_help_method: bool,
 }
@@ -319,6 +762,7 @@ impl RecordGenerator {
             ty: A::to_typename(),
             fields: Default::default(),
             static_fields: Default::default(),
+            field_functions: Default::default(),
             methods: Default::default(),
             mut_methods: Default::default(),
             functions: Default::default(),
@@ -327,8 +771,18 @@ impl RecordGenerator {
             meta_method_mut: Default::default(),
             meta_function: Default::default(),
             meta_function_mut: Default::default(),
+            dynamic_index: None,
+            constructor: None,
             type_doc: Default::default(),
             next_docs: Default::default(),
+            next_function_docs: Default::default(),
+            deprecated: None,
+            since: None,
+            stability: Stability::Stable,
+            next_deprecation: None,
+            next_since: None,
+            next_stability: None,
+            next_placement: None,
         }
     }
     /// creates an iterator that goes over the various method and function fields
@@ -343,6 +797,104 @@ impl RecordGenerator {
             .chain(self.meta_function.iter())
             .chain(self.meta_function_mut.iter())
     }
+    ///removes every field and function that isn't documented on the given side, see [Placement].
+    ///
+    ///The members that are left are documented on both sides afterwards, so this only has an effect once.
+    pub fn retain_placement(&mut self, side: Placement) {
+        for fields in [
+            &mut self.fields,
+            &mut self.static_fields,
+            &mut self.field_functions,
+        ] {
+            fields.retain(|v| v.placement.is_shown_on(side));
+            for field in fields {
+                field.placement = Placement::Both;
+            }
+        }
+        for functions in [
+            &mut self.functions,
+            &mut self.mut_functions,
+            &mut self.meta_function,
+            &mut self.meta_function_mut,
+        ] {
+            functions.retain(|v| v.placement.is_shown_on(side));
+            for function in functions {
+                function.placement = Placement::Both;
+            }
+        }
+        if let Some(constructor) = &self.constructor {
+            if !constructor.placement.is_shown_on(side) {
+                self.constructor = None;
+            }
+        }
+    }
+    ///if the function is the `new` function or `__call` meta function added by `add_constructor`
+    pub(crate) fn is_constructor(&self, function: &ExportedFunction) -> bool {
+        match &self.constructor {
+            //`__call` gets the proxy as its first parameter, followed by the ones of the constructor
+            Some(constructor) if function.is_meta_method => {
+                function.name == "__call"
+                    && function.params.get(1..) == Some(constructor.params.as_slice())
+                    && function.returns == constructor.returns
+            }
+            Some(_) => function.name == "new",
+            None => false,
+        }
+    }
+    ///every problem found in the signatures of the meta methods, see [ExportedFunction::meta_method_warnings]
+    pub fn meta_method_warnings(&self) -> Vec<String> {
+        self.all_functions()
+            .flat_map(ExportedFunction::meta_method_warnings)
+            .collect()
+    }
+    ///removes every field and function that is less stable than `max`, together with their documentation
+    pub fn retain_stability(&mut self, max: Stability) {
+        let mut removed = Vec::new();
+        for fields in [
+            &mut self.fields,
+            &mut self.static_fields,
+            &mut self.field_functions,
+        ] {
+            fields.retain(|v| {
+                let keep = v.stability <= max;
+                if !keep {
+                    removed.push(v.name.clone());
+                }
+                keep
+            });
+        }
+        for functions in [
+            &mut self.methods,
+            &mut self.mut_methods,
+            &mut self.functions,
+            &mut self.mut_functions,
+            &mut self.meta_method,
+            &mut self.meta_method_mut,
+            &mut self.meta_function,
+            &mut self.meta_function_mut,
+        ] {
+            functions.retain(|v| {
+                let keep = v.stability <= max;
+                if !keep {
+                    removed.push(v.name.clone());
+                }
+                keep
+            });
+        }
+        for name in removed {
+            let still_used = self
+                .fields
+                .iter()
+                .chain(self.static_fields.iter())
+                .chain(self.field_functions.iter())
+                .map(|v| &v.name)
+                .chain(self.all_functions().map(|v| &v.name))
+                .any(|v| v == &name);
+            if !still_used {
+                self.documentation.remove(&name);
+            }
+        }
+    }
 }
 
 impl RecordGenerator {
@@ -381,6 +933,49 @@ impl RecordGenerator {
         self.type_doc.push('\n');
         self
     }
+    ///the structured documentation that gets added to the next function.
+    pub fn next_function_docs(&mut self) -> &mut FunctionDocs {
+        self.next_function_docs.get_or_insert_with(Default::default)
+    }
+    ///marks the next field or function as deprecated
+    pub fn deprecate(&mut self, reason: &str, since: Option<&str>) -> &mut Self {
+        self.next_deprecation = Some(Deprecation::new(reason, since));
+        self
+    }
+    ///sets the version in which the next field or function got added
+    pub fn since(&mut self, version: &str) -> &mut Self {
+        self.next_since = Some(version.to_owned());
+        self
+    }
+    ///sets how stable the next field or function is
+    pub fn stability(&mut self, stability: Stability) -> &mut Self {
+        self.next_stability = Some(stability);
+        self
+    }
+    ///sets on which type the next static field or function gets documented
+    pub fn placement(&mut self, placement: Placement) -> &mut Self {
+        self.next_placement = Some(placement);
+        self
+    }
+    #[cfg(feature = "mlua")]
+    fn take_function_docs(&mut self, mut function: ExportedFunction) -> ExportedFunction {
+        if let Some(docs) = self.next_function_docs.take() {
+            function.docs = docs;
+        }
+        function.deprecated = self.next_deprecation.take();
+        function.since = self.next_since.take();
+        function.stability = self.next_stability.take().unwrap_or_default();
+        function.placement = self.next_placement.take().unwrap_or_default();
+        function
+    }
+    #[cfg(feature = "mlua")]
+    fn take_field_data(&mut self, mut field: Field) -> Field {
+        field.deprecated = self.next_deprecation.take();
+        field.since = self.next_since.take();
+        field.stability = self.next_stability.take().unwrap_or_default();
+        field.placement = self.next_placement.take().unwrap_or_default();
+        field
+    }
 }
 
 #[cfg(feature = "mlua")]
@@ -418,11 +1013,12 @@ where
         R: ToLuaMultiM + TealMultiValue,
     {
         self.copy_docs(name.as_ref().as_bytes());
-        self.methods.push(get_method_data::<A, R, _>(
+        let function = self.take_function_docs(get_method_data::<A, R, _>(
             name,
             false,
             Some(T::to_typename()),
-        ))
+        ));
+        self.methods.push(function)
     }
 
     fn add_function<S, A, R, F>(&mut self, name: S, _: F)
@@ -455,8 +1051,8 @@ where
         FR: std::future::Future<Output = ResultM<R>>,
     {
         self.copy_docs(name.as_ref().as_bytes());
-        self.functions
-            .push(get_method_data::<A, R, _>(name, false, None))
+        let function = self.take_function_docs(get_method_data::<A, R, _>(name, false, None));
+        self.functions.push(function)
     }
 
     fn add_meta_method<A, R, M>(&mut self, name: MetaMethodM, _: M)
@@ -465,12 +1061,8 @@ where
         R: ToLuaMultiM + TealMultiValue,
         M: 'static + MaybeSend + Fn(&Lua, &T, A) -> ResultM<R>,
     {
-        self.copy_docs(name.name().as_bytes());
-        self.meta_method.push(get_method_data::<A, R, _>(
-            &get_meta_name_mlua(name),
-            true,
-            Some(T::to_typename()),
-        ))
+        let function = self.document_meta_function::<A, R>(name, Some(T::to_typename()));
+        self.meta_method.push(function)
     }
 
     fn add_meta_method_mut<A, R, M>(&mut self, name: MetaMethodM, _: M)
@@ -479,12 +1071,8 @@ where
         R: ToLuaMultiM + TealMultiValue,
         M: 'static + MaybeSend + FnMut(&Lua, &mut T, A) -> ResultM<R>,
     {
-        self.copy_docs(name.name().as_bytes());
-        self.meta_method_mut.push(get_method_data::<A, R, _>(
-            &get_meta_name_mlua(name),
-            true,
-            Some(T::to_typename()),
-        ))
+        let function = self.document_meta_function::<A, R>(name, Some(T::to_typename()));
+        self.meta_method_mut.push(function)
     }
     fn add_meta_function<A, R, F>(&mut self, name: MetaMethodM, _: F)
     where
@@ -492,12 +1080,8 @@ where
         R: ToLuaMultiM + TealMultiValue,
         F: 'static + MaybeSend + Fn(&Lua, A) -> ResultM<R>,
     {
-        self.copy_docs(name.name().as_bytes());
-        self.meta_function.push(get_method_data::<A, R, _>(
-            get_meta_name_mlua(name).as_ref(),
-            true,
-            None,
-        ))
+        let function = self.document_meta_function::<A, R>(name, None);
+        self.meta_function.push(function)
     }
 
     fn add_meta_function_mut<A, R, F>(&mut self, name: MetaMethodM, _: F)
@@ -506,12 +1090,8 @@ where
         R: ToLuaMultiM + TealMultiValue,
         F: 'static + MaybeSend + FnMut(&Lua, A) -> ResultM<R>,
     {
-        self.copy_docs(name.name().as_bytes());
-        self.meta_function_mut.push(get_method_data::<A, R, _>(
-            &get_meta_name_mlua(name),
-            true,
-            None,
-        ))
+        let function = self.document_meta_function::<A, R>(name, None);
+        self.meta_function_mut.push(function)
     }
 
     fn document(&mut self, documentation: &str) -> &mut Self {
@@ -521,11 +1101,80 @@ where
     fn document_type(&mut self, documentation: &str) -> &mut Self {
         self.document_type(documentation)
     }
+    fn document_param(&mut self, name: &str, documentation: &str) -> &mut Self {
+        self.next_function_docs()
+            .document_param(name, documentation);
+        self
+    }
+    fn document_return(&mut self, documentation: &str) -> &mut Self {
+        self.next_function_docs().document_return(documentation);
+        self
+    }
+    fn document_error(&mut self, documentation: &str) -> &mut Self {
+        self.next_function_docs().document_error(documentation);
+        self
+    }
+    fn document_example(&mut self, code: &str) -> &mut Self {
+        self.next_function_docs().document_example(code);
+        self
+    }
+    fn deprecate(&mut self, reason: &str, since: Option<&str>) -> &mut Self {
+        self.deprecate(reason, since)
+    }
+    fn since(&mut self, version: &str) -> &mut Self {
+        self.since(version)
+    }
+    fn stability(&mut self, stability: Stability) -> &mut Self {
+        self.stability(stability)
+    }
+    fn placement(&mut self, placement: Placement) -> &mut Self {
+        self.placement(placement)
+    }
     fn generate_help(&mut self) {
-        self.functions
-            .push(get_method_data::<Option<String>, String, _>(
-                "help", false, None,
-            ))
+        let function = self.take_function_docs(get_method_data::<Option<String>, String, _>(
+            "help", false, None,
+        ));
+        self.functions.push(function);
+        self.functions.push(get_method_data::<
+            Option<String>,
+            Vec<HashMap<String, String>>,
+            _,
+        >("help_entries", false, None));
+    }
+    #[cfg(feature = "derive")]
+    fn generate_type_info(&mut self) {
+        let function = self.take_function_docs(get_method_data::<(), RecordGenerator, _>(
+            TYPE_INFO_FUNCTION,
+            false,
+            None,
+        ));
+        self.functions.push(function);
+    }
+
+    fn add_dynamic_index<K, V, F>(&mut self, index: F)
+    where
+        K: FromLuaM + ToTypename,
+        V: ToLuaM + ToTypename,
+        F: 'static + MaybeSend + Fn(&Lua, &T, K) -> ResultM<Option<V>>,
+    {
+        self.dynamic_index = Some(MapRepresentation {
+            key: Box::new(K::to_typename()),
+            value: Box::new(V::to_typename()),
+        });
+        <Self as TealDataMethodsM<T>>::add_meta_method(self, MetaMethodM::Index, index);
+    }
+
+    fn add_constructor<A, R, F>(&mut self, _: F)
+    where
+        A: FromLuaMultiM + TealMultiValue,
+        R: ToLuaMultiM + TealMultiValue,
+        F: 'static + MaybeSend + Fn(&Lua, A) -> ResultM<R>,
+    {
+        self.copy_docs(b"new");
+        let constructor = self.take_function_docs(get_method_data::<A, R, _>("new", false, None));
+        //the `__call` meta function only exists on the proxy, so it gets documented by `UserDataProxy`
+        self.functions.push(constructor.clone());
+        self.constructor = Some(constructor);
     }
 }
 
@@ -537,6 +1186,18 @@ where
     fn document(&mut self, documentation: &str) {
         self.document(documentation)
     }
+    fn deprecate(&mut self, reason: &str, since: Option<&str>) {
+        self.deprecate(reason, since);
+    }
+    fn since(&mut self, version: &str) {
+        self.since(version);
+    }
+    fn stability(&mut self, stability: Stability) {
+        self.stability(stability);
+    }
+    fn placement(&mut self, placement: Placement) {
+        self.placement(placement);
+    }
 
     fn add_field_method_get<S, R, M>(&mut self, name: S, _: M)
     where
@@ -562,7 +1223,7 @@ where
         R: mlua::IntoLua + ToTypename,
         F: 'static + MaybeSend + Fn(&Lua, mlua::AnyUserData) -> mlua::Result<R>,
     {
-        self.add_field::<S, R>(name);
+        self.add_static_field::<S, R>(name);
     }
 
     fn add_field_function_set<S, A, F>(&mut self, name: S, _: F)
@@ -571,7 +1232,15 @@ where
         A: mlua::FromLua + ToTypename,
         F: 'static + MaybeSend + FnMut(&Lua, mlua::AnyUserData, A) -> mlua::Result<()>,
     {
-        self.add_field::<S, A>(name);
+        self.add_static_field::<S, A>(name);
+    }
+
+    fn add_constant<S, V>(&mut self, name: S, value: V)
+    where
+        S: AsRef<str> + ToString,
+        V: 'static + MaybeSend + Clone + mlua::IntoLua + ToTypename + crate::ToLuaLiteral,
+    {
+        self.document_constant(name, &value);
     }
 
     fn add_meta_field_with<R, F>(&mut self, meta: MetaMethodM, _: F)
@@ -579,16 +1248,44 @@ where
         F: 'static + MaybeSend + Fn(&Lua) -> mlua::Result<R>,
         R: mlua::IntoLua + ToTypename,
     {
-        let x = Into::<MetaMethodM>::into(meta);
-        let name: Cow<'_, str> = Cow::Owned(x.name().to_string());
-        self.copy_docs(name.as_bytes());
-        self.static_fields
-            .push((NameContainer::from(name), R::to_typename()).into());
+        self.document_meta_field::<R>(meta);
     }
 }
 
 #[cfg(feature = "mlua")]
 impl RecordGenerator {
+    ///documents a meta method or function, the caller adds it to the list it belongs in
+    pub(crate) fn document_meta_function<A: TealMultiValue, R: TealMultiValue>(
+        &mut self,
+        meta: MetaMethodM,
+        self_type: Option<Type>,
+    ) -> ExportedFunction {
+        self.copy_docs(meta.name().as_bytes());
+        self.take_function_docs(get_method_data::<A, R, _>(
+            &get_meta_name_mlua(meta),
+            true,
+            self_type,
+        ))
+    }
+    ///documents a field that always has the given value
+    pub(crate) fn document_constant<S, V>(&mut self, name: S, value: &V)
+    where
+        S: AsRef<str>,
+        V: ToTypename + crate::ToLuaLiteral,
+    {
+        self.copy_docs(name.as_ref().as_bytes());
+        let mut field = Field::from((name.as_ref().as_bytes().to_vec().into(), V::to_typename()));
+        field.value = Some(value.to_lua_literal());
+        let field = self.take_field_data(field);
+        self.fields.push(field);
+    }
+    ///documents a meta field
+    pub(crate) fn document_meta_field<R: ToTypename>(&mut self, meta: MetaMethodM) {
+        let name: Cow<'_, str> = Cow::Owned(meta.name().to_string());
+        self.copy_docs(name.as_bytes());
+        let field = self.take_field_data((NameContainer::from(name), R::to_typename()).into());
+        self.static_fields.push(field);
+    }
     ///documents that this type has a field of the given type and name when exposed to lua
     pub fn add_field<S, R>(&mut self, name: S)
     where
@@ -596,8 +1293,20 @@ impl RecordGenerator {
         R: ToTypename,
     {
         self.copy_docs(name.as_ref().as_bytes());
-        self.fields
-            .push((name.as_ref().as_bytes().to_vec().into(), R::to_typename()).into());
+        let field = self
+            .take_field_data((name.as_ref().as_bytes().to_vec().into(), R::to_typename()).into());
+        self.fields.push(field);
+    }
+    ///documents that this type has a field of the given type and name that can also be reached through its proxy
+    pub fn add_static_field<S, R>(&mut self, name: S)
+    where
+        S: AsRef<str> + ToString,
+        R: ToTypename,
+    {
+        self.copy_docs(name.as_ref().as_bytes());
+        let field = self
+            .take_field_data((name.as_ref().as_bytes().to_vec().into(), R::to_typename()).into());
+        self.field_functions.push(field);
     }
     /// documents that this type has a method of the given type and name when exposed to lua
     pub fn add_method<
@@ -610,11 +1319,12 @@ impl RecordGenerator {
         name: S,
     ) {
         self.copy_docs(name.as_ref().as_bytes());
-        self.methods.push(get_method_data::<A, R, _>(
+        let function = self.take_function_docs(get_method_data::<A, R, _>(
             name,
             false,
             Some(T::to_typename()),
-        ))
+        ));
+        self.methods.push(function)
     }
     /// documents that this type has a method of the given type and name when exposed to lua
     pub fn add_method_mut<
@@ -627,11 +1337,12 @@ impl RecordGenerator {
         name: S,
     ) {
         self.copy_docs(name.as_ref().as_bytes());
-        self.mut_methods.push(get_method_data::<A, R, _>(
+        let function = self.take_function_docs(get_method_data::<A, R, _>(
             name,
             false,
             Some(T::to_typename()),
-        ))
+        ));
+        self.mut_methods.push(function)
     }
     /// documents that this type has a function of the given type and name when exposed to lua
     pub fn add_function<S: ToString + AsRef<str>, A: TealMultiValue, R: TealMultiValue>(
@@ -639,8 +1350,8 @@ impl RecordGenerator {
         name: S,
     ) {
         self.copy_docs(name.as_ref().as_bytes());
-        self.functions
-            .push(get_method_data::<A, R, _>(name, false, None))
+        let function = self.take_function_docs(get_method_data::<A, R, _>(name, false, None));
+        self.functions.push(function)
     }
     /// documents that this type has a function of the given type and name when exposed to lua
     pub fn add_function_mut<S: ToString + AsRef<str>, A: TealMultiValue, R: TealMultiValue>(
@@ -648,7 +1359,7 @@ impl RecordGenerator {
         name: S,
     ) {
         self.copy_docs(name.as_ref().as_bytes());
-        self.mut_functions
-            .push(get_method_data::<A, R, _>(name, false, None))
+        let function = self.take_function_docs(get_method_data::<A, R, _>(name, false, None));
+        self.mut_functions.push(function)
     }
 }
@@ -5,11 +5,11 @@ macro_rules! impl_type_name_life_time {
     ($teal_type:literal $current_type:ty) => {
         impl ToTypename for $current_type {
             fn to_typename() -> Type {
-                Type::Single(SingleType {
-                    name: $teal_type.into(),
-                    kind: KindOfType::Builtin,
-                    generics: vec![],
-                })
+                Type::Single(SingleType::new(
+                    $teal_type.into(),
+                    KindOfType::Builtin,
+                    vec![],
+                ))
             }
         }
     };
@@ -19,11 +19,7 @@ macro_rules! impl_type_name {
     ($teal_type:literal $current_type:ty) => {
         impl ToTypename for $current_type {
             fn to_typename() -> Type {
-                Type::Single(SingleType {
-                    name: $teal_type.into(),
-                    kind: KindOfType::Builtin,
-                    generics: vec![],
-                })
+                Type::Single(SingleType::new($teal_type.into(), KindOfType::Builtin, vec![]))
             }
         }
     };
@@ -263,6 +259,14 @@ impl<T: ToTypename> ToTypename for Option<T> {
     }
 }
 
+//only needed for `SingleType::bound`, which is why it isn't implemented for every `Box<T>`
+#[cfg(all(feature = "mlua", feature = "derive"))]
+impl ToTypename for Box<Type> {
+    fn to_typename() -> Type {
+        Type::to_typename()
+    }
+}
+
 impl<K: ToTypename, V: ToTypename> ToTypename for HashMap<K, V> {
     fn to_typename() -> Type {
         Type::Map(crate::MapRepresentation {
@@ -1,4 +1,4 @@
-use crate::{ToTypename, Type, TypeBody, TypeGenerator};
+use crate::{Deprecation, Placement, Stability, ToTypename, Type, TypeBody, TypeGenerator};
 
 #[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
 ///Used to document what global instances get made by the module
@@ -13,10 +13,70 @@ use crate::{ToTypename, Type, TypeBody, TypeGenerator};
 pub struct GlobalInstance {
     ///the name of the instance
     pub name: String,
+    ///the tables this instance is nested in, outermost first.
+    ///
+    ///For an instance added as `game.physics.raycast` this is `["game", "physics"]`
+    #[serde(default)]
+    pub namespace: Vec<String>,
     ///the type
     pub ty: Type,
     ///documentation for this global
     pub doc: String,
+    ///the value of this global as lua code, if it is a constant
+    #[serde(default)]
+    pub value: Option<String>,
+    ///set if this global is deprecated
+    #[serde(default)]
+    pub deprecated: Option<Deprecation>,
+    ///the version in which this global got added
+    #[serde(default)]
+    pub since: Option<String>,
+    ///how stable this global is
+    #[serde(default)]
+    pub stability: Stability,
+}
+
+impl GlobalInstance {
+    ///the name of this instance, including the namespace it is part of.
+    ///```
+    ///# use tealr::{GlobalInstance, Type, KindOfType, Stability};
+    ///let instance = GlobalInstance {
+    ///    name: "raycast".into(),
+    ///    namespace: vec!["game".into(), "physics".into()],
+    ///    ty: Type::new_single("integer", KindOfType::Builtin),
+    ///    doc: String::new(),
+    ///    value: None,
+    ///    deprecated: None,
+    ///    since: None,
+    ///    stability: Stability::Stable,
+    ///};
+    ///assert_eq!(instance.full_name(), "game.physics.raycast");
+    ///```
+    pub fn full_name(&self) -> String {
+        self.namespace
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once(self.name.as_str()))
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
+///Used to document the instances that are exposed as a module, loadable through `require`
+#[cfg_attr(
+    all(feature = "mlua", feature = "derive"),
+    derive(crate::mlu::FromToLua, crate::ToTypename)
+)]
+#[cfg_attr(
+    all(feature = "mlua", feature = "derive"),
+    tealr(tealr_name = crate)
+)]
+pub struct ExportedModule {
+    ///the name used to `require` the module
+    pub name: String,
+    ///the instances that make up the module
+    pub instances: Vec<GlobalInstance>,
 }
 
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
@@ -52,6 +112,14 @@ pub struct TypeWalker {
     pub given_types: Vec<TypeGenerator>,
     ///list of items that
     pub global_instances_off: Vec<GlobalInstance>,
+    ///list of modules that can be loaded using `require`
+    #[serde(default)]
+    pub modules: Vec<ExportedModule>,
+    ///If set, only these entries of the lua standard library are available (for example `string.format`).
+    ///
+    ///`None` means that the standard library is left untouched.
+    #[serde(default)]
+    pub available_std_lib: Option<Vec<String>>,
     ///list of extra pages that need to be generated.
     pub extra_page: Vec<ExtraPage>,
 }
@@ -62,6 +130,8 @@ impl Default for TypeWalker {
             tealr_version_used: crate::get_tealr_version().to_string(),
             given_types: Default::default(),
             global_instances_off: Default::default(),
+            modules: Default::default(),
+            available_std_lib: Default::default(),
             extra_page: Default::default(),
         }
     }
@@ -100,18 +170,64 @@ impl TypeWalker {
         match &mut x {
             TypeGenerator::Record(x) => {
                 x.should_be_inlined = true;
+                x.retain_placement(Placement::Instance);
             }
-            TypeGenerator::Enum(_) => (),
+            TypeGenerator::Enum(_) | TypeGenerator::TaggedUnion(_) => (),
         }
         self.given_types.push(x);
         self
     }
     ///prepares a type to have a `.d.tl` file generated, and adds it to the list of types to generate.
+    ///
+    ///Static members that are only meant to be documented on the proxy of the type are left out, see [Placement].
     pub fn process_type<A: ToTypename + TypeBody>(mut self) -> Self {
-        let x = <A as TypeBody>::get_type_body();
+        let mut x = <A as TypeBody>::get_type_body();
+        if let TypeGenerator::Record(x) = &mut x {
+            x.retain_placement(Placement::Instance);
+        }
         self.given_types.push(x);
         self
     }
+    ///removes every type, member and global instance that is less stable than `max`.
+    ///
+    ///Use `filter_stability(Stability::Stable)` to only keep what is part of the stable, public api.
+    ///```
+    ///# use tealr::{Stability, TypeWalker};
+    ///let walker = TypeWalker::new().filter_stability(Stability::Experimental);
+    ///```
+    pub fn filter_stability(mut self, max: Stability) -> Self {
+        self.given_types.retain(|v| match v {
+            TypeGenerator::Record(x) => x.stability <= max,
+            TypeGenerator::Enum(x) => x.stability <= max,
+            TypeGenerator::TaggedUnion(x) => x.stability <= max,
+        });
+        for ty in &mut self.given_types {
+            if let TypeGenerator::Record(x) = ty {
+                x.retain_stability(max);
+            }
+        }
+        self.global_instances_off.retain(|v| v.stability <= max);
+        for module in &mut self.modules {
+            module.instances.retain(|v| v.stability <= max);
+        }
+        self
+    }
+    ///every problem found in the signatures of the meta methods of every type, prefixed with the name of the type.
+    ///
+    ///Useful to check in a test that every meta method can actually be used by lua.
+    pub fn meta_method_warnings(&self) -> Vec<String> {
+        self.given_types
+            .iter()
+            .filter_map(|v| v.record())
+            .flat_map(|record| {
+                let name = crate::type_to_string(&record.ty, false);
+                record
+                    .meta_method_warnings()
+                    .into_iter()
+                    .map(move |warning| format!("{}: {}", name, warning))
+            })
+            .collect()
+    }
     /// Generates the json needed by [tealr_doc_gen](https://crates.io/crates/tealr_doc_gen) to generate the documentation.
     ///
     /// It is up to you to store it properly
@@ -146,4 +262,20 @@ impl TypeWalker {
         self.global_instances_off.append(&mut collector.instances);
         Ok(self)
     }
+    #[cfg(feature = "mlua")]
+    ///collect every instance that is shared with lua as a module, rather than as globals.
+    ///
+    ///The counterpart of [set_module_env](crate::mlu::set_module_env)
+    pub fn document_module<T: crate::mlu::ExportInstances>(
+        mut self,
+        module_name: impl Into<String>,
+    ) -> mlua::Result<Self> {
+        let mut collector = crate::export_instance::InstanceWalker::new();
+        T::default().add_instances(&mut collector)?;
+        self.modules.push(ExportedModule {
+            name: module_name.into(),
+            instances: collector.instances,
+        });
+        Ok(self)
+    }
 }
@@ -0,0 +1,437 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::ToTokens;
+use venial::{Error, Fields, Item};
+
+use crate::from_to_lua::{
+    allow_deprecated, conversion_type, find_tag_with_value, get_tealr_name, has_tealr_flag,
+    Payload, Representation,
+};
+
+pub(crate) fn impl_mlua_from_lua_exact_derive(ast: &Item) -> TokenStream {
+    let tealr_name = get_tealr_name(ast.attributes());
+    let allow_deprecated = allow_deprecated(ast);
+    let mlua = quote! {#tealr_name::mlu::mlua};
+    let (name, generic_params, generic_args, where_clause) = match ast {
+        Item::Struct(x) => (
+            &x.name,
+            x.generic_params.to_token_stream(),
+            x.get_inline_generic_args().map(|v| v.to_token_stream()),
+            x.where_clause.as_ref(),
+        ),
+        Item::Enum(x) => (
+            &x.name,
+            x.generic_params.to_token_stream(),
+            x.get_inline_generic_args().map(|v| v.to_token_stream()),
+            x.where_clause.as_ref(),
+        ),
+        _ => {
+            return Error::new("FromLuaExact can only be derived for structs and enums")
+                .to_compile_error()
+        }
+    };
+    let generic_args = generic_args.unwrap_or_default();
+    let extra_bounds = where_clause
+        .map(|v| v.items.to_token_stream())
+        .unwrap_or_default();
+    let mismatch = mismatch(name, &tealr_name);
+
+    let representation = match ast {
+        Item::Enum(x) => Representation::find(&x.attributes),
+        _ => None,
+    };
+
+    let (bounds, body) = match (ast, representation) {
+        (Item::Enum(x), Some(representation)) => exact_enum(x, &representation, &tealr_name),
+        (Item::Enum(x), None) if x.is_c_enum() => {
+            let not_a_string = mismatch(quote! {Some("expected a string".to_string())});
+            let unknown_variant =
+                mismatch(quote! {Some(::std::format!("unknown variant: {}", error))});
+            (
+                quote! {Self: #mlua::FromLua,},
+                quote! {
+                    match value {
+                        #mlua::Value::String(_) => match <Self as #mlua::FromLua>::from_lua(value.clone(), lua) {
+                            Err(error @ #mlua::Error::FromLuaConversionError { .. }) => Err(#unknown_variant),
+                            x => x,
+                        },
+                        _ => Err(#not_a_string),
+                    }
+                },
+            )
+        }
+        (Item::Enum(_), None) => {
+            let mismatch = mismatch(quote! {None});
+            (
+                quote! {Self: #mlua::FromLua + 'static,},
+                quote! {
+                    match value {
+                        #mlua::Value::UserData(ref x) if x.is::<Self>() => <Self as #mlua::FromLua>::from_lua(value, lua),
+                        _ => Err(#mismatch),
+                    }
+                },
+            )
+        }
+        (Item::Struct(x), _) if has_tealr_flag("userdata", &x.attributes) => {
+            let mismatch = mismatch(quote! {None});
+            (
+                quote! {Self: ::std::clone::Clone + 'static,},
+                quote! {
+                    match value {
+                        #mlua::Value::UserData(ref x) if x.is::<Self>() => Ok(x.borrow::<Self>()?.clone()),
+                        _ => Err(#mismatch),
+                    }
+                },
+            )
+        }
+        (Item::Struct(x), _) => {
+            if let Fields::Unit = x.fields {
+                return Error::new("Unit structs are not supported.").to_compile_error();
+            }
+            let not_a_table = mismatch(quote! {Some("expected a table".to_string())});
+            let (bounds, read) =
+                read_fields(&x.fields, quote! {Self}, None, &tealr_name, &mismatch);
+            (
+                bounds,
+                quote! {
+                    let table = match value {
+                        #mlua::Value::Table(ref x) => x,
+                        _ => return Err(#not_a_table),
+                    };
+                    #read
+                },
+            )
+        }
+        _ => unreachable!(),
+    };
+
+    quote! {
+        #allow_deprecated
+        impl #generic_params #tealr_name::mlu::FromLuaExact for #name #generic_args where #bounds #extra_bounds {
+            fn from_lua_exact(value: #mlua::Value, lua: &#mlua::Lua) -> #mlua::Result<Self> {
+                #body
+            }
+        }
+    }
+}
+
+///creates the error for when the value does not fit the type, expecting the value to be called `value`
+fn mismatch<'a>(
+    name: &'a proc_macro2::Ident,
+    tealr_name: &'a TokenStream,
+) -> impl Fn(TokenStream) -> TokenStream + 'a {
+    move |message| {
+        quote! {
+            #tealr_name::mlu::mlua::Error::FromLuaConversionError {
+                from: value.type_name(),
+                to: stringify!(#name).to_string(),
+                message: #message,
+            }
+        }
+    }
+}
+
+///the bounds and body of `from_lua_exact` for an enum that is stored as plain lua values.
+///
+///This is also used as `from_lua` for untagged enums, as there trying every variant only works if the conversions are exact.
+pub(crate) fn exact_enum(
+    enumeration: &venial::Enum,
+    representation: &Representation,
+    tealr_name: &TokenStream,
+) -> (TokenStream, TokenStream) {
+    let mlua = quote! {#tealr_name::mlu::mlua};
+    let mismatch = mismatch(&enumeration.name, tealr_name);
+    let mut bounds = TokenStream::new();
+    let mut branches = TokenStream::new();
+    //externally tagged enums store variants without a value as a string instead of a table
+    let mut string_branches = TokenStream::new();
+    let mut attempts = TokenStream::new();
+    for (variant, _) in enumeration.variants.iter() {
+        let payload = match Payload::of(variant, representation) {
+            Ok(x) => x,
+            Err(x) => return (TokenStream::new(), x),
+        };
+        let variant_name = &variant.name;
+        let tag_value = find_tag_with_value("rename", &variant.attributes)
+            .unwrap_or_else(|| quote! {stringify!(#variant_name)});
+        let constructor = quote! {Self::#variant_name};
+        //reads the variant out of `payload`
+        let read = match (&payload, representation) {
+            (_, Representation::Internal(tag)) => {
+                let (variant_bounds, read) = read_fields(
+                    &variant.fields,
+                    constructor.clone(),
+                    Some(tag),
+                    tealr_name,
+                    &mismatch,
+                );
+                bounds.extend(variant_bounds);
+                read
+            }
+            (Payload::Unit, _) => {
+                let mismatch =
+                    mismatch(quote! {Some(::std::format!("{} does not hold a value", #tag_value))});
+                quote! {
+                    match payload {
+                        #mlua::Value::Nil => Ok(#constructor),
+                        _ => Err(#mismatch),
+                    }
+                }
+            }
+            (Payload::Value(field), _) => {
+                let ty = &field.ty;
+                let (conversion, into) = conversion_type(ty, &field.attributes);
+                if find_tag_with_value("remote", &field.attributes).is_some() {
+                    bounds.extend(quote! {#ty: ::std::convert::From<#conversion>,});
+                }
+                bounds.extend(quote! {#conversion: #tealr_name::mlu::FromLuaExact,});
+                let mismatch = mismatch(quote! {Some(::std::format!("{}: {}", #tag_value, error))});
+                quote! {
+                    match <#conversion as #tealr_name::mlu::FromLuaExact>::from_lua_exact(payload, lua) {
+                        Ok(x) => Ok(#constructor(x #into)),
+                        Err(error @ #mlua::Error::FromLuaConversionError { .. }) => Err(#mismatch),
+                        Err(error) => Err(error),
+                    }
+                }
+            }
+            (Payload::Fields(_), _) => {
+                let (variant_bounds, read) = read_fields(
+                    &variant.fields,
+                    constructor.clone(),
+                    None,
+                    tealr_name,
+                    &mismatch,
+                );
+                bounds.extend(variant_bounds);
+                let not_a_table =
+                    mismatch(quote! {Some(::std::format!("{} expects a table", #tag_value))});
+                quote! {{
+                    let table = match payload {
+                        #mlua::Value::Table(x) => x,
+                        _ => return Err(#not_a_table),
+                    };
+                    #read
+                }}
+            }
+        };
+        match (&payload, representation) {
+            (_, Representation::Untagged) => {
+                attempts.extend(quote! {
+                    let attempt = || -> #mlua::Result<Self> {
+                        let payload = value.clone();
+                        #read
+                    };
+                    match attempt() {
+                        Ok(x) => return Ok(x),
+                        Err(error @ #mlua::Error::FromLuaConversionError { .. }) => errors.push(error.to_string()),
+                        Err(error) => return Err(error),
+                    }
+                });
+            }
+            (Payload::Unit, Representation::External) => string_branches.extend(quote! {
+                #tag_value => Ok(#constructor),
+            }),
+            _ => branches.extend(quote! {
+                #tag_value => {#read}
+            }),
+        }
+    }
+
+    let not_a_table = mismatch(quote! {Some("expected a table".to_string())});
+    let unknown_key = mismatch(quote! {
+        Some(::std::format!("unexpected key `{}`", key.to_string()?))
+    });
+    let body = match representation {
+        Representation::Internal(tag) => {
+            let unknown_tag = mismatch(quote! {
+                Some(::std::format!("`{}` is not one of the variants", #tag))
+            });
+            quote! {
+                let table = match value {
+                    #mlua::Value::Table(ref x) => x.clone(),
+                    _ => return Err(#not_a_table),
+                };
+                let tag = match table.raw_get::<#mlua::Value>(#tag)? {
+                    #mlua::Value::String(x) => x.to_str()?.to_owned(),
+                    _ => return Err(#unknown_tag),
+                };
+                match tag.as_str() {
+                    #branches
+                    _ => Err(#unknown_tag),
+                }
+            }
+        }
+        Representation::Adjacent(tag, content) => {
+            let unknown_tag = mismatch(quote! {
+                Some(::std::format!("`{}` is not one of the variants", #tag))
+            });
+            quote! {
+                let table = match value {
+                    #mlua::Value::Table(ref x) => x,
+                    _ => return Err(#not_a_table),
+                };
+                for key in table.pairs::<#mlua::Value, #mlua::Value>() {
+                    let (key, _) = key?;
+                    let is_known = match <::std::string::String as #tealr_name::mlu::FromLuaExact>::from_lua_exact(key.clone(), lua) {
+                        Ok(x) => x == #tag || x == #content,
+                        Err(_) => false,
+                    };
+                    if !is_known {
+                        return Err(#unknown_key);
+                    }
+                }
+                let tag = match table.raw_get::<#mlua::Value>(#tag)? {
+                    #mlua::Value::String(x) => x.to_str()?.to_owned(),
+                    _ => return Err(#unknown_tag),
+                };
+                let payload = table.raw_get::<#mlua::Value>(#content)?;
+                match tag.as_str() {
+                    #branches
+                    _ => Err(#unknown_tag),
+                }
+            }
+        }
+        Representation::External => {
+            let unknown_variant = mismatch(quote! {
+                Some(::std::format!("`{}` is not one of the variants", tag))
+            });
+            let single_field = mismatch(quote! {
+                Some("expected a table with exactly one field, named after the variant".to_string())
+            });
+            quote! {
+                let table = match value {
+                    #mlua::Value::String(ref x) => {
+                        let tag = x.to_str()?.to_owned();
+                        return match tag.as_str() {
+                            #string_branches
+                            _ => Err(#unknown_variant),
+                        };
+                    }
+                    #mlua::Value::Table(ref x) => x,
+                    _ => return Err(#not_a_table),
+                };
+                let mut pairs = table.pairs::<#mlua::Value, #mlua::Value>();
+                let (tag, payload) = match (pairs.next(), pairs.next()) {
+                    (Some(x), None) => x?,
+                    _ => return Err(#single_field),
+                };
+                let tag = match tag {
+                    #mlua::Value::String(x) => x.to_str()?.to_owned(),
+                    _ => return Err(#single_field),
+                };
+                match tag.as_str() {
+                    #branches
+                    _ => Err(#unknown_variant),
+                }
+            }
+        }
+        Representation::Untagged => {
+            let no_match = mismatch(quote! {
+                Some(::std::format!("none of the variants matched:\n{}", errors.join("\n")))
+            });
+            quote! {
+                let mut errors = ::std::vec::Vec::<::std::string::String>::new();
+                #attempts
+                Err(#no_match)
+            }
+        }
+    };
+    (bounds, body)
+}
+
+///the bounds needed to read the given fields out of `table`, and the code that does so.
+///
+///Keys that are neither a field nor the tag are rejected.
+fn read_fields(
+    fields: &Fields,
+    constructor: TokenStream,
+    tag: Option<&TokenStream>,
+    tealr_name: &TokenStream,
+    mismatch: &impl Fn(TokenStream) -> TokenStream,
+) -> (TokenStream, TokenStream) {
+    let mlua = quote! {#tealr_name::mlu::mlua};
+    let key_type = match fields {
+        Fields::Tuple(_) => quote! {usize},
+        _ => quote! {::std::string::String},
+    };
+    let fields: Vec<_> = match fields {
+        Fields::Unit => Vec::new(),
+        Fields::Tuple(x) => x
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(key, (field, _))| {
+                let member = Literal::usize_unsuffixed(key).into_token_stream();
+                (
+                    member.clone(),
+                    member.clone(),
+                    member,
+                    &field.ty,
+                    &field.attributes,
+                )
+            })
+            .collect(),
+        Fields::Named(x) => x
+            .fields
+            .iter()
+            .map(|(field, _)| {
+                let name = &field.name;
+                (
+                    name.to_token_stream(),
+                    quote! {stringify!(#name)},
+                    quote! {::std::string::String::from(stringify!(#name))},
+                    &field.ty,
+                    &field.attributes,
+                )
+            })
+            .collect(),
+    };
+    let mut bounds = TokenStream::new();
+    let mut getters = TokenStream::new();
+    let mut known_keys: Vec<_> = tag
+        .map(|v| quote! {::std::string::String::from(#v)})
+        .into_iter()
+        .collect();
+    for (member, key, owned_key, ty, attributes) in fields {
+        let (conversion_type, into) = match find_tag_with_value("remote", attributes) {
+            Some(remote) => {
+                bounds.extend(quote! {#ty: ::std::convert::From<#remote>,});
+                (remote, quote! {.into()})
+            }
+            None => (ty.to_token_stream(), quote! {}),
+        };
+        bounds.extend(quote! {#conversion_type: #tealr_name::mlu::FromLuaExact,});
+        let field_mismatch = mismatch(quote! {
+            Some(::std::format!("field `{}`: {}", #key, error))
+        });
+        getters.extend(quote! {
+            #member: match <#conversion_type as #tealr_name::mlu::FromLuaExact>::from_lua_exact(table.raw_get::<#mlua::Value>(#key)?, lua) {
+                Ok(x) => x #into,
+                Err(error @ #mlua::Error::FromLuaConversionError { .. }) => return Err(#field_mismatch),
+                Err(error) => return Err(error),
+            },
+        });
+        known_keys.push(owned_key);
+    }
+    let unknown_key = mismatch(quote! {
+        Some(::std::format!("unexpected key `{}`", key.to_string()?))
+    });
+    (
+        bounds,
+        quote! {
+            let known_keys: &[#key_type] = &[#(#known_keys),*];
+            for key in table.pairs::<#mlua::Value, #mlua::Value>() {
+                let (key, _) = key?;
+                let is_known = match <#key_type as #tealr_name::mlu::FromLuaExact>::from_lua_exact(key.clone(), lua) {
+                    Ok(x) => known_keys.contains(&x),
+                    Err(_) => false,
+                };
+                if !is_known {
+                    return Err(#unknown_key);
+                }
+            }
+            Ok(#constructor {
+                #getters
+            })
+        },
+    )
+}
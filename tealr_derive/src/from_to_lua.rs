@@ -1,4 +1,4 @@
-use proc_macro2::{Literal, Span, TokenStream};
+use proc_macro2::{Literal, Span, TokenStream, TokenTree};
 use quote::ToTokens;
 use venial::{parse_item, Struct};
 
@@ -32,7 +32,10 @@ fn debug_macro(ts: TokenStream) -> TokenStream {
     ts
 }
 
-fn find_tag_with_value(to_find: &str, tags: &[venial::Attribute]) -> Option<TokenStream> {
+pub(crate) fn find_tag_with_value(
+    to_find: &str,
+    tags: &[venial::Attribute],
+) -> Option<TokenStream> {
     tags.iter()
         .filter(|v| v.path.iter().cloned().collect::<TokenStream>().to_string() == "tealr")
         .filter_map(|v| match &v.value {
@@ -49,6 +52,15 @@ fn find_tag_with_value(to_find: &str, tags: &[venial::Attribute]) -> Option<Toke
         .next()
 }
 
+pub(crate) fn has_tealr_flag(to_find: &str, tags: &[venial::Attribute]) -> bool {
+    tags.iter()
+        .filter(|v| v.path.iter().cloned().collect::<TokenStream>().to_string() == "tealr")
+        .any(|v| match &v.value {
+            venial::AttributeValue::Group(_, y) => y.len() == 1 && y[0].to_string() == to_find,
+            _ => false,
+        })
+}
+
 fn find_doc_tags(tags: &[venial::Attribute]) -> impl Iterator<Item = String> + '_ {
     tags.iter()
         .filter(|v| {
@@ -69,6 +81,64 @@ fn find_doc_tags(tags: &[venial::Attribute]) -> impl Iterator<Item = String> + '
         })
 }
 
+///`#[allow(deprecated)]` if the item, or one of its fields or variants, is marked as `#[deprecated]`.
+///
+///The generated code uses them, which would warn in the crate of the user otherwise
+pub(crate) fn allow_deprecated(item: &impl ToTokens) -> TokenStream {
+    fn has_deprecated(tokens: TokenStream) -> bool {
+        let mut tokens = tokens.into_iter().peekable();
+        while let Some(token) = tokens.next() {
+            match token {
+                TokenTree::Punct(x) if x.as_char() == '#' => {
+                    let attribute = match tokens.peek() {
+                        Some(TokenTree::Group(x)) => x.stream().into_iter().next(),
+                        _ => None,
+                    };
+                    if matches!(attribute, Some(TokenTree::Ident(x)) if x == "deprecated") {
+                        return true;
+                    }
+                }
+                TokenTree::Group(x) if has_deprecated(x.stream()) => return true,
+                _ => (),
+            }
+        }
+        false
+    }
+    if has_deprecated(item.to_token_stream()) {
+        quote! {#[allow(deprecated)]}
+    } else {
+        quote! {}
+    }
+}
+
+///finds the `#[deprecated]` attribute and turns it into the code to create the matching `Deprecation`
+fn find_deprecation(
+    tags: &[venial::Attribute],
+    deprecation_location: &TokenStream,
+) -> Option<TokenStream> {
+    let tag = tags
+        .iter()
+        .find(|v| v.path.iter().cloned().collect::<TokenStream>().to_string() == "deprecated")?;
+    let mut reason = quote! {""};
+    let mut since = quote! {::std::option::Option::None};
+    match &tag.value {
+        venial::AttributeValue::Empty => (),
+        venial::AttributeValue::Equals(_, y) => reason = y.iter().cloned().collect(),
+        venial::AttributeValue::Group(_, y) => {
+            for part in y.split(|v| matches!(v, TokenTree::Punct(x) if x.as_char() == ',')) {
+                if let [TokenTree::Ident(key), TokenTree::Punct(_), value] = part {
+                    match key.to_string().as_str() {
+                        "note" => reason = value.to_token_stream(),
+                        "since" => since = quote! {::std::option::Option::Some(#value)},
+                        _ => (),
+                    }
+                }
+            }
+        }
+    }
+    Some(quote! {#deprecation_location::new(#reason, #since)})
+}
+
 fn add_commas(mut v: Vec<TokenStream>) -> TokenStream {
     let mut push_into = Vec::new();
     for value in v.drain(0..(v.len() - 1)) {
@@ -103,9 +173,56 @@ struct BasicConfig {
     teal_data_methods_location: TokenStream,
     invalid_enum_variant_error: TokenStream,
     typename_macro: TokenStream,
+    tealr_name: TokenStream,
+}
+
+///the code that sets the deprecation, version and stability of a type or field, based on its attributes
+fn metadata(tags: &[venial::Attribute], tealr_name: &TokenStream) -> TokenStream {
+    let deprecation_location = quote! {#tealr_name::Deprecation};
+    let deprecated = find_deprecation(tags, &deprecation_location)
+        .map(|v| quote! {target.deprecated = ::std::option::Option::Some(#v);});
+    let since = find_tag_with_value("since", tags).map(|v| {
+        quote! {target.since = ::std::option::Option::Some(::std::string::ToString::to_string(#v));}
+    });
+    let stability = find_tag_with_value("stability", tags)
+        .map(|v| quote! {target.stability = #tealr_name::Stability::#v;});
+    quote! {#deprecated #since #stability}
+}
+
+///the code that sets the stability of an enum, which only has a stability and no other metadata
+fn enum_stability(tags: &[venial::Attribute], tealr_name: &TokenStream) -> TokenStream {
+    find_tag_with_value("stability", tags)
+        .map(|v| quote! {gen.stability = #tealr_name::Stability::#v;})
+        .unwrap_or_default()
+}
+
+fn last_field_metadata(tags: &[venial::Attribute], tealr_name: &TokenStream) -> TokenStream {
+    let metadata = metadata(tags, tealr_name);
+    if metadata.is_empty() {
+        return metadata;
+    }
+    quote! {
+        if let ::std::option::Option::Some(target) = gen.fields.last_mut() {
+            #metadata
+        }
+    }
+}
+
+pub(crate) fn type_metadata(tags: &[venial::Attribute], tealr_name: &TokenStream) -> TokenStream {
+    let metadata = metadata(tags, tealr_name);
+    if metadata.is_empty() {
+        return metadata;
+    }
+    quote! {
+        {
+            let target = &mut gen;
+            #metadata
+        }
+    }
 }
 
 fn implement_for_struct(structure: Struct, config: BasicConfig) -> TokenStream {
+    let allow_deprecated = allow_deprecated(&structure);
     let to_loc = config.to_location;
     let from_loc = config.from_location;
     let create_table = config.create_table;
@@ -120,6 +237,7 @@ fn implement_for_struct(structure: Struct, config: BasicConfig) -> TokenStream {
     let record_generator_loc = config.record_generator_loc;
     let name = &structure.name;
     let to_lua_name = config.to_lua_name;
+    let tealr_name = config.tealr_name;
 
     let (to_add, (to_remove, type_body)): (TokenStream, (TokenStream, TokenStream)) =
         match structure.fields {
@@ -149,6 +267,7 @@ fn implement_for_struct(structure: Struct, config: BasicConfig) -> TokenStream {
                     let docs = find_doc_tags(&x.0.attributes).map(|v| quote! {
                         gen.document(#v);
                     }).collect::<TokenStream>();
+                    let metadata = last_field_metadata(&x.0.attributes, &tealr_name);
                     (
                         quote! {table.set(#key,#set_value)?;},
                         (
@@ -161,6 +280,7 @@ fn implement_for_struct(structure: Struct, config: BasicConfig) -> TokenStream {
                                         ::std::convert::From::from((::std::borrow::Cow::Borrowed(#name).into(),
                                         <(#type_name) as #type_name_path>::to_typename()))
                                     );
+                                #metadata
                             },
                         ),
                     )
@@ -191,6 +311,7 @@ fn implement_for_struct(structure: Struct, config: BasicConfig) -> TokenStream {
                     let docs = find_doc_tags(&field.attributes).map(|v| quote! {
                         gen.document(#v);
                     }).collect::<TokenStream>();
+                    let metadata = last_field_metadata(&field.attributes, &tealr_name);
                     (
                         quote! { table.set(stringify!(#name),#set_value)?;},
                         (
@@ -203,6 +324,7 @@ fn implement_for_struct(structure: Struct, config: BasicConfig) -> TokenStream {
                                         ::std::convert::From::from((::std::borrow::Cow::Borrowed(stringify!(#name)).into(),
                                         <(#type_name) as #type_name_path>::to_typename()))
                                     );
+                                #metadata
                                 gen.copy_docs(stringify!(#name).as_bytes());
                             },
                         ),
@@ -213,7 +335,9 @@ fn implement_for_struct(structure: Struct, config: BasicConfig) -> TokenStream {
     let document_type = find_doc_tags(&structure.attributes)
         .map(|v| quote! {gen.document_type(#v);})
         .collect::<TokenStream>();
+    let type_metadata = type_metadata(&structure.attributes, &tealr_name);
     quote! {
+        #allow_deprecated
         impl #to_loc for #name {
             fn #to_lua_name(self, #lua_location) -> #result_location_to {
                 let mut table = #create_table()?;
@@ -221,6 +345,7 @@ fn implement_for_struct(structure: Struct, config: BasicConfig) -> TokenStream {
                 lua.pack(table)
             }
         }
+        #allow_deprecated
         impl #from_loc for #name {
             fn from_lua(lua_value:#lua_value, #lua_location) -> #result_location_from {
                 let as_table = match lua_value {
@@ -232,10 +357,12 @@ fn implement_for_struct(structure: Struct, config: BasicConfig) -> TokenStream {
                 })
             }
         }
+        #allow_deprecated
         impl #type_body_loc for #name {
             fn get_type_body()-> #type_generator_loc {
                 let mut gen = #record_generator_loc::new::<Self>(false);
                 #document_type
+                #type_metadata
                 #type_body
                 <#type_generator_loc as ::std::convert::From<_>>::from(gen)
             }
@@ -244,14 +371,20 @@ fn implement_for_struct(structure: Struct, config: BasicConfig) -> TokenStream {
 }
 
 fn implement_for_enum(enumeration: venial::Enum, config: BasicConfig) -> TokenStream {
+    if let Some(representation) = Representation::find(&enumeration.attributes) {
+        return implement_for_tagged_enum(enumeration, representation, config);
+    }
     if enumeration.is_c_enum() {
         return implement_for_c_enum(enumeration, config);
     }
+    let allow_deprecated = allow_deprecated(&enumeration);
     let call_fields = find_tag_with_value("extend_fields", &enumeration.attributes)
         .map(|v| quote! {#v(fields)})
         .unwrap_or_else(|| quote! {});
     let call_methods = find_tag_with_value("extend_methods", &enumeration.attributes)
         .map(|v| quote! {#v(methods)});
+    let generate_type_info = has_tealr_flag("type_info", &enumeration.attributes)
+        .then(|| quote! {methods.generate_type_info();});
     let name = enumeration.name;
     let user_data_location = config.user_data_location;
     let user_data_fields_location = config.user_data_fields_location;
@@ -264,6 +397,8 @@ fn implement_for_enum(enumeration: venial::Enum, config: BasicConfig) -> TokenSt
     let teal_data_methods_location = config.teal_data_methods_location;
     let record_generator_loc = config.record_generator_loc;
     let type_name_macro = config.typename_macro;
+    let tealr_name = &config.tealr_name;
+    let type_metadata = type_metadata(&enumeration.attributes, tealr_name);
 
     let has_userdata_fields = config.has_userdata_fields;
     let (add_fields_user_data, add_fields_teal_data, add_fields_type_body) = if has_userdata_fields
@@ -408,18 +543,26 @@ fn implement_for_enum(enumeration: venial::Enum, config: BasicConfig) -> TokenSt
         .map(|v| quote! {gen.document_type(#v);})
         .collect::<TokenStream>();
     let mut trait_impls = quote! {
+        #allow_deprecated
         impl #user_data_location for #name {
             #add_fields_user_data
             fn add_methods<M: #user_data_methods_location<Self>>(methods: &mut M) {
                 let mut wrapper = #user_data_wrapper_location::from_user_data_methods(methods);
                 <Self as #teal_data_location>::add_methods(&mut wrapper)
             }
+            fn register(registry: &mut #tealr_name::mlu::mlua::UserDataRegistry<Self>) {
+                let mut wrapper = #user_data_wrapper_location::from_user_data_registry(registry);
+                <Self as #teal_data_location>::add_fields(&mut wrapper);
+                <Self as #teal_data_location>::add_methods(&mut wrapper)
+            }
         }
+        #allow_deprecated
         impl #teal_data_location for #name {
             #add_fields_teal_data
             fn add_methods<T: #teal_data_methods_location<Self>>(methods: &mut T) {
                 #variant_functions;
                 #call_methods;
+                #generate_type_info
                 methods.add_method("GetTypeName",|_,this,()|{
                     Ok(match this {
                         #is_of_branches
@@ -428,11 +571,13 @@ fn implement_for_enum(enumeration: venial::Enum, config: BasicConfig) -> TokenSt
                 #creator_functions
             }
         }
+        #allow_deprecated
         impl #type_body_loc for #name {
             fn get_type_body() -> #type_generator_loc {
                 let mut gen = #record_generator_loc::new::<Self>(false);
                 gen.is_user_data = true;
                 #document_type
+                #type_metadata
                 #add_fields_type_body;
                 <Self as #teal_data_location>::add_methods(&mut gen);
                 <#type_generator_loc as ::std::convert::From<_>>::from(gen)
@@ -507,6 +652,7 @@ fn implement_for_enum(enumeration: venial::Enum, config: BasicConfig) -> TokenSt
     let error_message = config.error_message;
     let result_location_from = config.result_location_from;
     let with_from_lua = quote! {
+        #allow_deprecated
         impl #from_loc for #name {
             fn from_lua(lua_value:#lua_value, #lua_location) -> #result_location_from {
                 match lua_value.as_userdata() {
@@ -523,7 +669,424 @@ fn implement_for_enum(enumeration: venial::Enum, config: BasicConfig) -> TokenSt
     trait_impls
 }
 
+///How an enum that is converted to plain lua values stores its variants, see `EnumRepresentation`
+pub(crate) enum Representation {
+    Internal(TokenStream),
+    External,
+    Adjacent(TokenStream, TokenStream),
+    Untagged,
+}
+
+impl Representation {
+    pub(crate) fn find(tags: &[venial::Attribute]) -> Option<Self> {
+        if let Some(tag) = find_tag_with_value("tagged", tags) {
+            return Some(Self::Internal(tag));
+        }
+        if has_tealr_flag("externally_tagged", tags) {
+            return Some(Self::External);
+        }
+        if let Some(tag) = find_tag_with_value("adjacently_tagged", tags) {
+            let content = find_tag_argument("adjacently_tagged", "content", tags)
+                .unwrap_or_else(|| quote! {"content"});
+            return Some(Self::Adjacent(tag, content));
+        }
+        if has_tealr_flag("untagged", tags) {
+            return Some(Self::Untagged);
+        }
+        None
+    }
+    fn to_generator(&self, tealr_name: &TokenStream) -> TokenStream {
+        let location = quote! {#tealr_name::EnumRepresentation};
+        match self {
+            Self::Internal(tag) => {
+                quote! {#location::InternallyTagged(::std::string::String::from(#tag))}
+            }
+            Self::External => quote! {#location::ExternallyTagged},
+            Self::Adjacent(tag, content) => quote! {
+                #location::AdjacentlyTagged(::std::string::String::from(#tag), ::std::string::String::from(#content))
+            },
+            Self::Untagged => quote! {#location::Untagged},
+        }
+    }
+}
+
+///finds `argument = value` in the same `#[tealr(...)]` attribute that starts with `to_find`
+fn find_tag_argument(
+    to_find: &str,
+    argument: &str,
+    tags: &[venial::Attribute],
+) -> Option<TokenStream> {
+    tags.iter()
+        .filter(|v| v.path.iter().cloned().collect::<TokenStream>().to_string() == "tealr")
+        .filter_map(|v| match &v.value {
+            venial::AttributeValue::Group(_, y)
+                if y.first().map(|v| v.to_string() == to_find).unwrap_or(false) =>
+            {
+                let position = y.iter().position(|v| v.to_string() == argument)?;
+                y.get(position + 2).map(|v| v.clone().into_token_stream())
+            }
+            _ => None,
+        })
+        .next()
+}
+
+///What a variant of an enum that is converted to plain lua values holds
+pub(crate) enum Payload<'a> {
+    Unit,
+    Value(&'a venial::TupleField),
+    Fields(Vec<&'a venial::NamedField>),
+}
+
+impl<'a> Payload<'a> {
+    pub(crate) fn of(
+        variant: &'a venial::EnumVariant,
+        representation: &Representation,
+    ) -> Result<Self, TokenStream> {
+        let error = |message| {
+            Err(venial::Error::new_at_span(variant.name.span(), message).to_compile_error())
+        };
+        match (&variant.fields, representation) {
+            (venial::Fields::Unit, _) => Ok(Self::Unit),
+            (venial::Fields::Named(x), _) => Ok(Self::Fields(x.fields.iter().map(|(field, _)| field).collect())),
+            (venial::Fields::Tuple(_), Representation::Internal(_)) => {
+                error("Variants of tagged enums need to have named fields or no fields at all")
+            }
+            (venial::Fields::Tuple(x), _) if x.fields.len() == 1 => Ok(Self::Value(&x.fields[0].0)),
+            (venial::Fields::Tuple(_), _) => error(
+                "Variants of enums stored as plain values can only hold named fields or a single unnamed value",
+            ),
+        }
+    }
+    ///the pattern that matches this variant, binding every field to its name or the value to `value`
+    pub(crate) fn pattern(&self, constructor: TokenStream) -> TokenStream {
+        match self {
+            Self::Unit => quote! {#constructor},
+            Self::Value(_) => quote! {#constructor(value)},
+            Self::Fields(fields) => {
+                let names = fields.iter().map(|v| &v.name);
+                quote! {#constructor { #(#names),* }}
+            }
+        }
+    }
+}
+
+///the type a field is converted through, which is the `remote` type if it has one, and the code that turns it into the type of the field
+pub(crate) fn conversion_type(
+    ty: &venial::TypeExpr,
+    attributes: &[venial::Attribute],
+) -> (TokenStream, TokenStream) {
+    match find_tag_with_value("remote", attributes) {
+        Some(remote) => (remote, quote! {.into()}),
+        None => (ty.to_token_stream(), quote! {}),
+    }
+}
+
+fn implement_for_tagged_enum(
+    enumeration: venial::Enum,
+    representation: Representation,
+    config: BasicConfig,
+) -> TokenStream {
+    let allow_deprecated = allow_deprecated(&enumeration);
+    let name = &enumeration.name;
+    let tealr_name = config.tealr_name;
+    let mlua = quote! {#tealr_name::mlu::mlua};
+    let to_loc = config.to_location;
+    let from_loc = config.from_location;
+    let create_table = config.create_table;
+    let result_location_to = config.result_location_to;
+    let result_location_from = config.result_location_from;
+    let lua_location = config.lua_type;
+    let lua_value = config.lua_value;
+    let error_message = config.error_message;
+    let type_name_path = config.type_name_path;
+    let type_body_loc = config.type_body_loc;
+    let type_generator_loc = config.type_generator_loc;
+    let to_lua_name = config.to_lua_name;
+    let document_type = find_doc_tags(&enumeration.attributes)
+        .map(|v| quote! {gen.document_type(#v);})
+        .collect::<TokenStream>();
+    let stability = enum_stability(&enumeration.attributes, &tealr_name);
+    let unknown_variant = |tag: TokenStream| {
+        quote! {
+            #mlua::Error::FromLuaConversionError {
+                from: "table",
+                to: stringify!(#name).to_string(),
+                message: Some(::std::format!("unknown {} `{}`", #tag, x)),
+            }
+        }
+    };
+
+    let mut to_branches = TokenStream::new();
+    let mut from_branches = TokenStream::new();
+    //externally tagged enums store variants without a value as a string instead of a table
+    let mut from_string_branches = TokenStream::new();
+    let mut variants = TokenStream::new();
+    //the lua value that holds the variant, which is only read for variants that have one
+    let payload_source = match &representation {
+        Representation::Internal(_) => quote! {#mlua::Value::Table(table.clone())},
+        Representation::Adjacent(_, content) => quote! {table.get::<#mlua::Value>(#content)?},
+        Representation::External | Representation::Untagged => quote! {content.clone()},
+    };
+    for (variant, _) in enumeration.variants.iter() {
+        let variant_name = &variant.name;
+        let tag_value = find_tag_with_value("rename", &variant.attributes)
+            .unwrap_or_else(|| quote! {stringify!(#variant_name)});
+        let payload = match Payload::of(variant, &representation) {
+            Ok(x) => x,
+            Err(x) => return x,
+        };
+        let pattern = payload.pattern(quote! {#name::#variant_name});
+        //sets the fields into `table`, reads the variant from `table` and documents it
+        let (set_fields, read_value, type_fields) = match &payload {
+            Payload::Unit => (quote! {}, quote! {#name::#variant_name}, quote! {}),
+            Payload::Value(field) => {
+                let ty = &field.ty;
+                let (conversion, into) = conversion_type(ty, &field.attributes);
+                (
+                    quote! {},
+                    quote! {#name::#variant_name(<#conversion as #from_loc>::from_lua(payload, lua)? #into)},
+                    quote! {variant.value = Some(<#conversion as #type_name_path>::to_typename());},
+                )
+            }
+            Payload::Fields(fields) => {
+                let mut set_fields = TokenStream::new();
+                let mut get_fields = TokenStream::new();
+                let mut type_fields = TokenStream::new();
+                for field in fields {
+                    let field_name = &field.name;
+                    let ty = &field.ty;
+                    let (conversion, into) = conversion_type(ty, &field.attributes);
+                    let docs = find_doc_tags(&field.attributes)
+                        .map(|v| quote! {variant.document_field(stringify!(#field_name), #v);})
+                        .collect::<TokenStream>();
+                    set_fields.extend(quote! {
+                        table.set(stringify!(#field_name), <#conversion as ::std::convert::From<#ty>>::from(#field_name))?;
+                    });
+                    get_fields.extend(quote! {#field_name: table.get::<#conversion>(stringify!(#field_name))? #into,});
+                    type_fields.extend(quote! {
+                        #docs
+                        variant
+                            .fields
+                            .push(
+                                ::std::convert::From::from((::std::borrow::Cow::Borrowed(stringify!(#field_name)).into(),
+                                <#conversion as #type_name_path>::to_typename()))
+                            );
+                    });
+                }
+                (
+                    set_fields,
+                    quote! {{
+                        let table = <#mlua::Table as #from_loc>::from_lua(payload, lua)?;
+                        #name::#variant_name { #get_fields }
+                    }},
+                    type_fields,
+                )
+            }
+        };
+        //the variant as a lua value on its own, without its tag
+        let payload_value = match &payload {
+            Payload::Unit => quote! {#mlua::Value::Nil},
+            Payload::Value(field) => {
+                let (conversion, _) = conversion_type(&field.ty, &field.attributes);
+                let ty = &field.ty;
+                quote! {#mlua::IntoLua::into_lua(<#conversion as ::std::convert::From<#ty>>::from(value), lua)?}
+            }
+            Payload::Fields(_) => quote! {{
+                let table = #create_table()?;
+                #set_fields
+                #mlua::Value::Table(table)
+            }},
+        };
+        let is_unit = matches!(payload, Payload::Unit);
+        let to_value = match &representation {
+            Representation::Internal(tag) => quote! {{
+                let table = #create_table()?;
+                table.set(#tag, #tag_value)?;
+                #set_fields
+                #mlua::Value::Table(table)
+            }},
+            Representation::External if is_unit => {
+                quote! {#mlua::IntoLua::into_lua(#tag_value, lua)?}
+            }
+            Representation::External => quote! {{
+                let outer = #create_table()?;
+                outer.set(#tag_value, #payload_value)?;
+                #mlua::Value::Table(outer)
+            }},
+            Representation::Adjacent(tag, _) if is_unit => quote! {{
+                let outer = #create_table()?;
+                outer.set(#tag, #tag_value)?;
+                #mlua::Value::Table(outer)
+            }},
+            Representation::Adjacent(tag, content) => quote! {{
+                let outer = #create_table()?;
+                outer.set(#tag, #tag_value)?;
+                outer.set(#content, #payload_value)?;
+                #mlua::Value::Table(outer)
+            }},
+            Representation::Untagged => payload_value,
+        };
+        to_branches.extend(quote! {#pattern => #to_value,});
+        let from_value = if is_unit {
+            quote! {#tag_value => #read_value,}
+        } else {
+            quote! {#tag_value => {
+                let payload = #payload_source;
+                #read_value
+            },}
+        };
+        match representation {
+            Representation::External if is_unit => from_string_branches.extend(from_value),
+            _ => from_branches.extend(from_value),
+        }
+        let document_variant = find_doc_tags(&variant.attributes)
+            .map(|doc| quote! {variant.document(#doc);})
+            .collect::<TokenStream>();
+        if document_variant.is_empty() && type_fields.is_empty() {
+            variants.extend(quote! {
+                gen.add_variant(stringify!(#variant_name), #tag_value);
+            });
+        } else {
+            variants.extend(quote! {
+                {
+                    let variant = gen.add_variant(stringify!(#variant_name), #tag_value);
+                    #document_variant
+                    #type_fields
+                }
+            });
+        }
+    }
+    //`lua` is only needed to convert the values that variants hold
+    let has_payload = enumeration
+        .variants
+        .iter()
+        .any(|(variant, _)| !matches!(variant.fields, venial::Fields::Unit));
+    let unused_lua = quote! {_: &#mlua::Lua};
+    let to_lua_location = match representation {
+        Representation::Untagged if !has_payload => &unused_lua,
+        _ => &lua_location,
+    };
+    let from_lua_location = if has_payload {
+        &lua_location
+    } else {
+        &unused_lua
+    };
+
+    let (from_bounds, from_body) = match &representation {
+        Representation::Internal(tag) => {
+            let unknown_variant = unknown_variant(tag.clone());
+            (
+                quote! {},
+                quote! {
+                    let table = match lua_value {
+                        #lua_value::Table(x) => x,
+                        x => Err(#error_message)?
+                    };
+                    let tag: ::std::string::String = table.get(#tag)?;
+                    Ok(match tag.as_str() {
+                        #from_branches
+                        x => Err(#unknown_variant)?
+                    })
+                },
+            )
+        }
+        Representation::Adjacent(tag, _) => {
+            let unknown_variant = unknown_variant(tag.clone());
+            (
+                quote! {},
+                quote! {
+                    let table = match lua_value {
+                        #lua_value::Table(x) => x,
+                        x => Err(#error_message)?
+                    };
+                    let tag: ::std::string::String = table.get(#tag)?;
+                    Ok(match tag.as_str() {
+                        #from_branches
+                        x => Err(#unknown_variant)?
+                    })
+                },
+            )
+        }
+        Representation::External => {
+            let unknown_variant = unknown_variant(quote! {"variant"});
+            let content = if from_branches.is_empty() {
+                quote! {_}
+            } else {
+                quote! {content}
+            };
+            (
+                quote! {},
+                quote! {
+                    let table = match lua_value {
+                        #lua_value::String(x) => {
+                            return Ok(match &*x.to_str()? {
+                                #from_string_branches
+                                x => Err(#unknown_variant)?
+                            })
+                        }
+                        #lua_value::Table(x) => x,
+                        x => Err(#error_message)?
+                    };
+                    let mut pairs = table.pairs::<::std::string::String, #mlua::Value>();
+                    let (tag, #content) = match (pairs.next(), pairs.next()) {
+                        (Some(x), None) => x?,
+                        _ => return Err(#mlua::Error::FromLuaConversionError {
+                            from: "table",
+                            to: stringify!(#name).to_string(),
+                            message: Some("expected a table with exactly one field, named after the variant".to_string()),
+                        }),
+                    };
+                    Ok(match tag.as_str() {
+                        #from_branches
+                        x => Err(#unknown_variant)?
+                    })
+                },
+            )
+        }
+        Representation::Untagged => {
+            let (bounds, body) =
+                crate::from_lua_exact::exact_enum(&enumeration, &representation, &tealr_name);
+            (
+                bounds,
+                quote! {
+                    let value = lua_value;
+                    #body
+                },
+            )
+        }
+    };
+    let representation = representation.to_generator(&tealr_name);
+
+    quote! {
+        #allow_deprecated
+        impl #to_loc for #name {
+            fn #to_lua_name(self, #to_lua_location) -> #result_location_to {
+                Ok(match self {
+                    #to_branches
+                })
+            }
+        }
+        #allow_deprecated
+        impl #from_loc for #name where #from_bounds {
+            fn from_lua(lua_value:#lua_value, #from_lua_location) -> #result_location_from {
+                #from_body
+            }
+        }
+        #allow_deprecated
+        impl #type_body_loc for #name {
+            fn get_type_body()-> #type_generator_loc {
+                let mut gen = #tealr_name::TaggedUnionGenerator::with_representation::<Self>(#representation);
+                #stability
+                #document_type
+                #variants
+                <#type_generator_loc as ::std::convert::From<_>>::from(gen)
+            }
+        }
+    }
+}
+
 fn implement_for_c_enum(enumeration: venial::Enum, config: BasicConfig) -> TokenStream {
+    let allow_deprecated = allow_deprecated(&enumeration);
     let name = enumeration.name;
     let type_body_loc = config.type_body_loc;
     let type_generator_loc = config.type_generator_loc;
@@ -536,6 +1099,7 @@ fn implement_for_c_enum(enumeration: venial::Enum, config: BasicConfig) -> Token
     let enum_generator_loc = config.enum_generator_loc;
     let invalid_enum_variant_error = config.invalid_enum_variant_error;
     let to_lua_name = config.to_lua_name;
+    let stability = enum_stability(&enumeration.attributes, &config.tealr_name);
     let document_type = find_doc_tags(&enumeration.attributes)
         .map(|v| quote! {gen.document_type(#v);})
         .collect::<TokenStream>();
@@ -546,16 +1110,22 @@ fn implement_for_c_enum(enumeration: venial::Enum, config: BasicConfig) -> Token
             .iter()
             .map(|(v, _)| {
                 let variant_name = &v.name;
+                let lua_name = find_tag_with_value("rename", &v.attributes)
+                    .unwrap_or_else(|| quote! {stringify!(#variant_name)});
+                let document_variant = find_doc_tags(&v.attributes)
+                    .map(|doc| quote! {gen.document_variant(#lua_name, #doc);})
+                    .collect::<TokenStream>();
                 (
-                    quote! {#name::#variant_name => stringify!(#variant_name),},
+                    quote! {#name::#variant_name => #lua_name,},
                     (
-                        quote! {stringify!(#variant_name) => #name::#variant_name,},
+                        quote! {#lua_name => #name::#variant_name,},
                         quote! {
                             gen
                                 .variants
                                 .push(
-                                    ::std::borrow::Cow::Borrowed(stringify!(#variant_name)).into(),
+                                    ::std::borrow::Cow::Borrowed(#lua_name).into(),
                                 );
+                            #document_variant
                         },
                     ),
                 )
@@ -563,6 +1133,7 @@ fn implement_for_c_enum(enumeration: venial::Enum, config: BasicConfig) -> Token
             .unzip();
 
     quote! {
+        #allow_deprecated
         impl #to_loc for #name {
             fn #to_lua_name(self, #lua_location) -> #result_location_to {
                 let res = match self {
@@ -571,6 +1142,7 @@ fn implement_for_c_enum(enumeration: venial::Enum, config: BasicConfig) -> Token
                 lua.pack(res.to_string())
             }
         }
+        #allow_deprecated
         impl #from_loc for #name {
             fn from_lua(lua_value:#lua_value, #lua_location) -> #result_location_from {
                 let x = <std::string::String as #from_loc>::from_lua(lua_value,lua)?;
@@ -580,9 +1152,11 @@ fn implement_for_c_enum(enumeration: venial::Enum, config: BasicConfig) -> Token
                 })
             }
         }
+        #allow_deprecated
         impl #type_body_loc for #name {
             fn get_type_body()-> #type_generator_loc {
                 let mut gen = #enum_generator_loc::new::<Self>();
+                #stability
                 #document_type;
                 #variants;
                 <#type_generator_loc as ::std::convert::From<_>>::from(gen)
@@ -629,6 +1203,7 @@ pub(crate) fn mlua_from_to_lua(input: TokenStream) -> TokenStream {
             message:None
         } },
         typename_macro: quote! {#tealr_name::ToTypename},
+        tealr_name: tealr_name.clone(),
     };
 
     match parsed {
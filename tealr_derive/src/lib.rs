@@ -14,8 +14,12 @@ extern crate quote;
 ))]
 mod embed_compiler;
 #[cfg(feature = "derive")]
+mod from_lua_exact;
+#[cfg(feature = "derive")]
 mod from_to_lua;
 #[cfg(feature = "derive")]
+mod union;
+#[cfg(feature = "derive")]
 mod user_data;
 
 #[cfg(any(
@@ -138,3 +142,17 @@ pub fn embed_compiler(input: TokenStream) -> TokenStream {
 pub fn mlua_from_to_lua(input: TokenStream) -> TokenStream {
     from_to_lua::mlua_from_to_lua(input.into()).into()
 }
+
+#[cfg(feature = "derive")]
+#[proc_macro_derive(MluaUnion, attributes(tealr))]
+pub fn mlua_union_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_item(input.into()).unwrap();
+    union::impl_mlua_union_derive(&ast).into()
+}
+
+#[cfg(feature = "derive")]
+#[proc_macro_derive(MluaFromLuaExact, attributes(tealr))]
+pub fn mlua_from_lua_exact_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_item(input.into()).unwrap();
+    from_lua_exact::impl_mlua_from_lua_exact_derive(&ast).into()
+}
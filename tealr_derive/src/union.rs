@@ -0,0 +1,96 @@
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use venial::{Error, Item};
+
+use crate::from_to_lua::get_tealr_name;
+
+pub(crate) fn impl_mlua_union_derive(ast: &Item) -> TokenStream {
+    let enumeration = match ast {
+        Item::Enum(x) => x,
+        _ => return Error::new("Unions can only be derived for enums").to_compile_error(),
+    };
+    let tealr_name = get_tealr_name(&enumeration.attributes);
+    let name = &enumeration.name;
+    let mut variant_names = Vec::new();
+    let mut variant_types = Vec::new();
+    for (variant, _) in enumeration.variants.iter() {
+        match variant.get_single_type() {
+            Some(field) => {
+                variant_names.push(&variant.name);
+                variant_types.push(field.ty.to_token_stream());
+            }
+            None => {
+                return Error::new_at_span(
+                    variant.name.span(),
+                    "Every variant of a union needs to hold exactly one unnamed field",
+                )
+                .to_compile_error()
+            }
+        }
+    }
+    let generic_params = enumeration.generic_params.to_token_stream();
+    let generic_args = enumeration
+        .get_inline_generic_args()
+        .map(|v| v.to_token_stream())
+        .unwrap_or_default();
+    let extra_bounds = enumeration
+        .where_clause
+        .as_ref()
+        .map(|v| v.items.to_token_stream())
+        .unwrap_or_default();
+    let where_clause = |bound: TokenStream| {
+        quote! {
+            where #(#variant_types: #bound,)* #extra_bounds
+        }
+    };
+    let into_lua_bound = where_clause(quote! {#tealr_name::mlu::mlua::IntoLua});
+    let from_lua_bound =
+        where_clause(quote! {#tealr_name::mlu::FromLuaExact + #tealr_name::ToTypename});
+    let to_typename_bound = where_clause(quote! {#tealr_name::ToTypename});
+    let mlua = quote! {#tealr_name::mlu::mlua};
+
+    quote! {
+        impl #generic_params #mlua::IntoLua for #name #generic_args #into_lua_bound {
+            fn into_lua(self, lua: &#mlua::Lua) -> #mlua::Result<#mlua::Value> {
+                match self {
+                    #(#name::#variant_names(x) => x.into_lua(lua),)*
+                }
+            }
+        }
+        impl #generic_params #tealr_name::mlu::FromLuaExact for #name #generic_args #from_lua_bound {
+            fn from_lua_exact(value: #mlua::Value, lua: &#mlua::Lua) -> #mlua::Result<Self> {
+                let mut attempts = ::std::vec::Vec::new();
+                #(
+                    match <#variant_types as #tealr_name::mlu::FromLuaExact>::from_lua_exact(value.clone(), lua) {
+                        Ok(x) => return Ok(#name::#variant_names(x)),
+                        Err(error @ #mlua::Error::FromLuaConversionError { .. }) => attempts.push(
+                            ::std::format!(
+                                "{}: {}",
+                                #tealr_name::type_to_string(&<#variant_types as #tealr_name::ToTypename>::to_typename(), false),
+                                error
+                            )
+                        ),
+                        Err(error) => return Err(error),
+                    }
+                )*
+                Err(#mlua::Error::FromLuaConversionError {
+                    from: value.type_name(),
+                    to: stringify!(#name).to_string(),
+                    message: Some(::std::format!("none of the alternatives matched:\n{}", attempts.join("\n"))),
+                })
+            }
+        }
+        impl #generic_params #mlua::FromLua for #name #generic_args #from_lua_bound {
+            fn from_lua(value: #mlua::Value, lua: &#mlua::Lua) -> #mlua::Result<Self> {
+                <Self as #tealr_name::mlu::FromLuaExact>::from_lua_exact(value, lua)
+            }
+        }
+        impl #generic_params #tealr_name::ToTypename for #name #generic_args #to_typename_bound {
+            fn to_typename() -> #tealr_name::Type {
+                #tealr_name::Type::Or(::std::vec![
+                    #(<#variant_types as #tealr_name::ToTypename>::to_typename(),)*
+                ])
+            }
+        }
+    }
+}
@@ -1,19 +1,26 @@
 use proc_macro2::{Ident, TokenStream};
 use venial::{Error, Item};
 
-use crate::from_to_lua::get_tealr_name;
+use crate::from_to_lua::{
+    allow_deprecated, find_tag_with_value, get_tealr_name, has_tealr_flag, type_metadata,
+};
 
 pub(crate) fn impl_type_representation_derive(ast: &Item) -> TokenStream {
     let name = ast.name();
     let tealr_name = get_tealr_name(ast.attributes());
+    let allow_deprecated = allow_deprecated(ast);
+    let proxy_name = find_tag_with_value("proxy_name", ast.attributes())
+        .map(|v| quote! {const PROXY_NAME: Option<&'static str> = Some(#v);});
     let gen = quote! {
+        #allow_deprecated
         impl #tealr_name::ToTypename for #name {
+            #proxy_name
             fn to_typename() -> #tealr_name::Type {
-                #tealr_name::Type::Single(#tealr_name::SingleType{
-                    name: #tealr_name::Name(::std::borrow::Cow::Borrowed(stringify!(#name))),
-                    kind: #tealr_name::KindOfType::External,
-                    generics: ::std::default::Default::default()
-                })
+                #tealr_name::Type::Single(#tealr_name::SingleType::new(
+                    #tealr_name::Name(::std::borrow::Cow::Borrowed(stringify!(#name))),
+                    #tealr_name::KindOfType::External,
+                    ::std::default::Default::default(),
+                ))
             }
         }
     };
@@ -25,18 +32,24 @@ fn generate_type_body(
     traits: TokenStream,
     extra_method: Option<TokenStream>,
     tealr_name: &TokenStream,
+    type_metadata: TokenStream,
+    after_methods: TokenStream,
+    allow_deprecated: &TokenStream,
 ) -> TokenStream {
     let extra_method = match extra_method {
         Some(x) => quote! {<Self as #traits>::#x(&mut gen);},
         None => quote!(),
     };
     quote! {
+        #allow_deprecated
         impl #tealr_name::TypeBody for #name {
             fn get_type_body() -> #tealr_name::TypeGenerator {
                 let mut gen = #tealr_name::RecordGenerator::new::<Self>(false);
                 gen.is_user_data = true;
+                #type_metadata
                 #extra_method
                 <Self as #traits>::add_methods(&mut gen);
+                #after_methods
                 <_ as ::std::convert::From<_>>::from(gen)
 
             }
@@ -45,7 +58,15 @@ fn generate_type_body(
 }
 
 pub(crate) fn impl_mlua_user_data_derive(ast: &Item) -> TokenStream {
+    let generate_type_info = |tealr_name: &TokenStream, methods: TokenStream| {
+        if has_tealr_flag("type_info", ast.attributes()) {
+            quote! {<_ as #tealr_name::mlu::TealDataMethods<Self>>::generate_type_info(&mut #methods);}
+        } else {
+            quote! {}
+        }
+    };
     let tealr_name = get_tealr_name(ast.attributes());
+    let allow_deprecated = allow_deprecated(ast);
     let name = match ast {
         Item::Struct(x) => &x.name,
         Item::Enum(x) => &x.name,
@@ -59,17 +80,29 @@ pub(crate) fn impl_mlua_user_data_derive(ast: &Item) -> TokenStream {
         quote! {#tealr_name::mlu::TealData},
         Some(quote!(add_fields)),
         &tealr_name,
+        type_metadata(ast.attributes(), &tealr_name),
+        generate_type_info(&tealr_name, quote! {gen}),
+        &allow_deprecated,
     );
+    let wrapper_type_info = generate_type_info(&tealr_name, quote! {x});
     quote! {
+        #allow_deprecated
         impl #tealr_name::mlu::mlua::UserData for #name {
             fn add_methods<T: #tealr_name::mlu::mlua::UserDataMethods<Self>>(methods: &mut T) {
                 let mut x = #tealr_name::mlu::UserDataWrapper::from_user_data_methods(methods);
                 <Self as #tealr_name::mlu::TealData>::add_methods(&mut x);
+                #wrapper_type_info
             }
             fn add_fields<F: #tealr_name::mlu::mlua::UserDataFields<Self>>(fields: &mut F) {
                 let mut wrapper = #tealr_name::mlu::UserDataWrapper::from_user_data_fields(fields);
                 <Self as #tealr_name::mlu::TealData>::add_fields(&mut wrapper)
             }
+            fn register(registry: &mut #tealr_name::mlu::mlua::UserDataRegistry<Self>) {
+                let mut x = #tealr_name::mlu::UserDataWrapper::from_user_data_registry(registry);
+                <Self as #tealr_name::mlu::TealData>::add_fields(&mut x);
+                <Self as #tealr_name::mlu::TealData>::add_methods(&mut x);
+                #wrapper_type_info
+            }
         }
         #type_body
     }
@@ -0,0 +1,602 @@
+#![allow(deprecated)]
+use std::{
+    collections::HashMap,
+    fs,
+    sync::{Arc, Mutex},
+};
+
+use tealr::{
+    mlu::{
+        mlua::{self, Lua, MetaMethod, Result, UserDataRegistry},
+        run_lua_examples, set_deprecation_hook, FromToLua, TealData, TealDataFields,
+        TealDataMethods, UserData, UserDataWrapper,
+    },
+    Deprecation, ExportedFunction, RecordGenerator, Stability, ToTypename, TypeBody, TypeGenerator,
+    TypeWalker,
+};
+
+#[derive(Clone, UserData, ToTypename)]
+struct Vector {}
+
+impl TealData for Vector {
+    fn add_methods<T: TealDataMethods<Self>>(methods: &mut T) {
+        methods.document_type("A point in 2d space");
+        methods.document("The length of the vector");
+        methods.add_method("length", |_, _, ()| Ok(1.0));
+    }
+    fn add_fields<F: TealDataFields<Self>>(fields: &mut F) {
+        fields.document("The <x> coordinate");
+        fields.add_field_method_get("x", |_, _| Ok(0.0));
+        fields.add_field_method_set("x", |_, _, _: f64| Ok(()));
+    }
+}
+
+mod physics {
+    use tealr::{
+        mlu::{TealData, UserData},
+        ToTypename,
+    };
+    ///has the same name as the other `Vector`
+    #[derive(Clone, UserData, ToTypename)]
+    pub(crate) struct Vector {}
+    impl TealData for Vector {}
+}
+
+#[derive(Clone, UserData, ToTypename)]
+#[tealr(since = "1.2.0")]
+struct Player {}
+
+impl TealData for Player {
+    fn add_methods<T: TealDataMethods<Self>>(methods: &mut T) {
+        methods.document_type("A player that is currently online");
+        methods
+            .document("Moves the player **instantly**")
+            .document_param("x", "Where the player should go")
+            .document_return("The new position of the player")
+            .document_example("game.player:teleport(10)")
+            .since("1.3.0");
+        methods.add_method("teleport", |_, _, _x: f64| Ok(Vector {}));
+        methods.deprecate("use `teleport` instead", Some("0.9.0"));
+        methods.add_method("move_to", |_, _, _x: f64| Ok(Vector {}));
+        methods.document("Sends a chat message to the player");
+        methods.add_method("send_message", |_, _, _message: String| Ok(()));
+        methods
+            .document("Might get removed again")
+            .stability(Stability::Experimental);
+        methods.add_method("fly", |_, _, ()| Ok(()));
+        methods.add_function("spawn", |_, ()| Ok(Player {}));
+        methods.document("Shows the name of the player");
+        methods.add_meta_method(MetaMethod::ToString, |_, _, ()| Ok("steve"));
+        methods.generate_help();
+        methods.generate_type_info();
+    }
+    fn add_fields<F: TealDataFields<Self>>(fields: &mut F) {
+        fields.document("The amount of health the player has left");
+        fields.add_field_method_get("health", |_, _| Ok(20));
+        fields.add_field_method_set("health", |_, _, _: i64| Ok(()));
+        fields.stability(Stability::Internal);
+        fields.add_field_method_get("entity_id", |_, _| Ok(1));
+    }
+}
+
+#[derive(Clone, UserData, ToTypename)]
+#[tealr(stability = Internal)]
+#[tealr(type_info)]
+struct Debugger {}
+
+impl TealData for Debugger {
+    fn add_methods<T: TealDataMethods<Self>>(methods: &mut T) {
+        methods.add_method("pause", |_, _, ()| Ok(()));
+    }
+}
+
+#[derive(Clone, UserData, ToTypename)]
+struct Counter {}
+
+impl TealData for Counter {
+    fn add_methods<T: TealDataMethods<Self>>(methods: &mut T) {
+        methods.document_type(
+            "Counts things.\n```lua\nassert(counter:get() == 1)\n```\nThis is not lua:\n```\nnot lua\n```",
+        );
+        methods
+            .document("Gets the current count")
+            .document_example("assert(counter:get() == 1)");
+        methods.add_method("get", |_, _, ()| Ok(1));
+        methods.document_example("assert(counter:reset() == 0)");
+        methods.add_method("reset", |_, _, ()| Ok(1));
+    }
+    fn add_fields<F: TealDataFields<Self>>(fields: &mut F) {
+        fields.document("The name\n```lua\nassert(counter.name == 'count')\n```");
+        fields.add_field_method_get("name", |_, _| Ok("count"));
+    }
+}
+
+//registers its members by hand through the wrapper instead of using the `TealData` implementation
+#[derive(ToTypename)]
+struct ManualCounter {}
+impl TealData for ManualCounter {}
+
+impl mlua::UserData for ManualCounter {
+    fn register(registry: &mut UserDataRegistry<Self>) {
+        let mut wrapper = UserDataWrapper::from_user_data_registry(registry);
+        TealDataFields::document(&mut wrapper, "The highest number it counts to");
+        wrapper.add_field_method_get("max", |_, _| Ok(100));
+        TealDataMethods::document(&mut wrapper, "Counts to the given number");
+        wrapper.add_method("count", |_, _, to: i64| Ok((1..=to).collect::<Vec<_>>()));
+        wrapper.generate_help();
+    }
+}
+
+#[derive(Clone, UserData, ToTypename)]
+#[deprecated(since = "0.9.0", note = "use `Calculator` instead")]
+struct OldCalculator {}
+
+impl TealData for OldCalculator {
+    fn add_methods<T: TealDataMethods<Self>>(methods: &mut T) {
+        methods
+            .document("Adds two numbers")
+            .deprecate("use `add` instead", Some("0.10"));
+        methods.add_method("plus", |_, _, (a, b): (i64, i64)| Ok(a + b));
+        methods.add_method("add", |_, _, (a, b): (i64, i64)| Ok(a + b));
+        methods.generate_help();
+    }
+    fn add_fields<F: TealDataFields<Self>>(fields: &mut F) {
+        fields.deprecate("there is no limit anymore", None);
+        fields.add_field_method_get("limit", |_, _| Ok(10));
+    }
+}
+
+#[derive(Clone, FromToLua, ToTypename)]
+struct Settings {
+    #[deprecated = "use `volume` instead"]
+    loudness: f64,
+    volume: f64,
+    #[tealr(since = "1.1.0")]
+    #[tealr(stability = Experimental)]
+    render_distance: i64,
+}
+
+#[derive(Clone, FromToLua, ToTypename)]
+#[tealr(stability = Experimental)]
+enum Weather {
+    Sunny,
+    Rainy,
+}
+
+//their pages would otherwise overwrite `index.html` and `Player.html`
+#[allow(non_camel_case_types)]
+#[derive(Clone, UserData, ToTypename)]
+struct index {}
+impl TealData for index {}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, UserData, ToTypename)]
+struct player {}
+impl TealData for player {}
+
+#[derive(Default)]
+struct Export;
+impl tealr::mlu::ExportInstances for Export {
+    fn add_instances<T: tealr::mlu::InstanceCollector>(
+        self,
+        instance_collector: &mut T,
+    ) -> mlua::Result<()> {
+        instance_collector
+            .document_instance("The player that is currently playing")
+            .since("1.0.0")
+            .add_instance("game.player", |_| Ok(Player {}))?
+            .stability(Stability::Internal)
+            .add_instance("debugger", |_| Ok(Debugger {}))?
+            .document_instance("Usage:\n  ```lua counter.lua\n  assert(counter ~= nil)\n  ```")
+            .add_instance("counter", |_| Ok(Counter {}))?
+            .deprecate("use `calculator` instead", Some("0.9"))
+            .add_instance("old_calculator", |_| Ok(OldCalculator {}))?
+            .add_instance("calculator", |_| Ok(OldCalculator {}))?
+            .deprecate("use `calculator:add` instead", None)
+            .add_function("add", |_, (a, b): (i64, i64)| Ok(a + b))?
+            .add_help()?;
+        Ok(())
+    }
+}
+
+fn record<T: TypeBody>() -> RecordGenerator {
+    match T::get_type_body() {
+        TypeGenerator::Record(x) => *x,
+        _ => panic!("expected a record"),
+    }
+}
+
+fn method<'a>(record: &'a RecordGenerator, name: &str) -> &'a ExportedFunction {
+    record
+        .methods
+        .iter()
+        .find(|v| v.name == name)
+        .expect("the method")
+}
+
+#[test]
+fn markdown() -> Result<()> {
+    let pages = TypeWalker::new()
+        .process_type::<Player>()
+        .process_type::<Vector>()
+        .document_global_instance::<Export>()?
+        .add_page("Getting started".to_owned(), "# Getting started".to_owned())
+        .to_markdown();
+    let names = pages
+        .iter()
+        .map(|v| v.file_name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "index.md",
+            "Player.md",
+            "Vector.md",
+            "page_Getting_started.md"
+        ]
+    );
+
+    let index = &pages[0].content;
+    assert!(index.contains("- [Player](Player.md)"));
+    assert!(index.contains("## Globals\n\n"));
+    assert!(index.contains("### game\n\n#### game.player\n\n"));
+    assert!(index.contains("The player that is currently playing"));
+    assert!(index.contains("- [Getting started](page_Getting_started.md)"));
+
+    let player = &pages[1].content;
+    assert!(player.starts_with("# Player\n\n"));
+    assert!(player.contains("## Methods\n\n### teleport"));
+    assert!(player.contains("[Vector](Vector.md)"));
+    assert!(player.contains("- `x`: Where the player should go"));
+    assert!(player.contains("```lua\ngame.player:teleport(10)\n```"));
+    assert!(player.contains("*deprecated since 0.9.0: use \\`teleport\\` instead*"));
+
+    let vector = &pages[2].content;
+    assert!(vector.contains("A point in 2d space"));
+    assert_eq!(vector.matches("### x").count(), 1);
+    assert_eq!(pages[3].content, "# Getting started");
+    Ok(())
+}
+
+#[test]
+fn same_names() {
+    let pages = TypeWalker::new()
+        .process_type::<Vector>()
+        .process_type::<physics::Vector>()
+        .add_page("Vector".to_owned(), "# Vectors".to_owned())
+        .to_markdown();
+    let names = pages
+        .iter()
+        .map(|v| v.file_name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        ["index.md", "Vector.md", "Vector_2.md", "page_Vector.md"]
+    );
+    assert!(pages[0].content.contains("- [Vector](Vector_2.md)"));
+    assert!(pages[1].content.contains("A point in 2d space"));
+    assert!(!pages[2].content.contains("A point in 2d space"));
+}
+
+#[test]
+fn html() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let dir = std::env::temp_dir().join(format!("tealr_html_test_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    TypeWalker::new()
+        .process_type::<Player>()
+        .process_type::<Vector>()
+        .process_type::<index>()
+        .process_type::<player>()
+        .document_global_instance::<Export>()?
+        .add_page("Getting started".to_owned(), "Hello".to_owned())
+        .write_html(&dir)?;
+
+    for file in [
+        "index.html",
+        "Player.html",
+        "Vector.html",
+        "page_Getting_started.html",
+        "index_2.html",
+        "player_2.html",
+        "style.css",
+        "search.js",
+        "search_index.js",
+    ] {
+        assert!(dir.join(file).exists(), "{} is missing", file);
+    }
+
+    let index = fs::read_to_string(dir.join("index.html"))?;
+    assert!(index.contains("<a href=\"Player.html\">Player</a>"));
+    assert!(index.contains("<h4 id=\"global.game_player\">game.player</h4>"));
+    assert!(!index.contains("http"));
+
+    let player = fs::read_to_string(dir.join("Player.html"))?;
+    assert!(player.contains("<a class=\"type\" href=\"Vector.html\">Vector</a>"));
+    assert!(player.contains("<span class=\"builtin\">number</span>"));
+    assert!(player.contains("game.player:teleport(10)"));
+    assert!(player.contains("Moves the player <strong>instantly</strong>"));
+
+    let vector = fs::read_to_string(dir.join("Vector.html"))?;
+    assert!(vector.contains("The &lt;x&gt; coordinate"));
+
+    let search: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.join("search_index.json"))?)?;
+    let names = search
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v["name"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert!(names.contains(&"Player.teleport"));
+    assert!(names.contains(&"Vector.x"));
+    assert!(names.contains(&"game.player"));
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn lua_examples() -> Result<()> {
+    let walker = TypeWalker::new()
+        .process_type::<Counter>()
+        .document_global_instance::<Export>()?;
+    let locations = walker
+        .lua_examples()
+        .iter()
+        .map(|v| v.location())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        locations,
+        [
+            "Counter",
+            "Counter.name",
+            "Counter.get",
+            "Counter.reset",
+            "counter"
+        ]
+    );
+
+    assert_eq!(walker.lua_examples()[4].code, "assert(counter ~= nil)\n");
+
+    let lua = Lua::new();
+    tealr::mlu::set_global_env(Export, &lua)?;
+    let failures = run_lua_examples(&lua, &walker).unwrap_err();
+    assert_eq!(failures.total, 5);
+    assert_eq!(failures.failures.len(), 1);
+    assert_eq!(failures.failures[0].example.location(), "Counter.reset");
+    assert!(failures
+        .to_string()
+        .contains("example of `Counter.reset` failed"));
+
+    //the examples of the player use the instance exported as `game.player`
+    let walker = TypeWalker::new().process_type::<Player>();
+    run_lua_examples(&lua, &walker).unwrap();
+    Ok(())
+}
+
+#[test]
+fn deprecation() -> Result<()> {
+    let calculator = record::<OldCalculator>();
+    assert_eq!(
+        calculator.deprecated,
+        Some(Deprecation::new("use `Calculator` instead", Some("0.9.0")))
+    );
+    assert_eq!(
+        method(&calculator, "plus").deprecated,
+        Some(Deprecation::new("use `add` instead", Some("0.10")))
+    );
+    assert_eq!(method(&calculator, "add").deprecated, None);
+    assert_eq!(
+        calculator.fields[0].deprecated.as_ref().unwrap().reason,
+        "there is no limit anymore"
+    );
+
+    let settings = record::<Settings>();
+    assert_eq!(
+        settings.fields[0].deprecated,
+        Some(Deprecation::new("use `volume` instead", None))
+    );
+    assert_eq!(settings.fields[1].deprecated, None);
+
+    let walker = TypeWalker::new().document_global_instance::<Export>()?;
+    let instance = |name: &str| {
+        walker
+            .global_instances_off
+            .iter()
+            .find(|v| v.name == name)
+            .expect("the instance")
+    };
+    assert_eq!(
+        instance("old_calculator")
+            .deprecated
+            .as_ref()
+            .unwrap()
+            .to_string(),
+        "deprecated since 0.9: use `calculator` instead"
+    );
+    assert_eq!(instance("calculator").deprecated, None);
+
+    let lua = Lua::new();
+    let warnings = Arc::new(Mutex::new(Vec::new()));
+    let hook_warnings = warnings.clone();
+    set_deprecation_hook(&lua, move |lua, message| {
+        hook_warnings.lock().unwrap().push(message.to_owned());
+        //the hook is free to change the app data
+        lua.set_app_data(message.len());
+        Ok(())
+    })?;
+    tealr::mlu::set_global_env(Export, &lua)?;
+    let code = "
+        assert(calculator:add(1, 2) == 3)
+        assert(calculator:plus(1, 2) == 3)
+        assert(old_calculator:plus(2, 2) == 4)
+        assert(calculator.limit == 10)
+        assert(old_calculator.limit == 10)
+        assert(add(1, 2) == 3)
+        assert(add(2, 2) == 4)
+        return calculator.help(\"plus\")
+    ";
+    let help: String = lua.load(code).set_name("deprecation").eval()?;
+    assert!(help.contains("deprecated since 0.10: use `add` instead"));
+    assert_eq!(
+        *warnings.lock().unwrap(),
+        [
+            "`OldCalculator.plus` is deprecated since 0.10: use `add` instead",
+            "`OldCalculator.limit` is deprecated: there is no limit anymore",
+            "`add` is deprecated: use `calculator:add` instead"
+        ]
+    );
+    assert!(lua.app_data_ref::<usize>().is_some());
+    Ok(())
+}
+
+#[test]
+fn stability() -> Result<()> {
+    let player = record::<Player>();
+    assert_eq!(player.since.as_deref(), Some("1.2.0"));
+    assert_eq!(player.stability, Stability::Stable);
+    assert_eq!(method(&player, "teleport").since.as_deref(), Some("1.3.0"));
+    assert_eq!(method(&player, "fly").since, None);
+    assert_eq!(method(&player, "fly").stability, Stability::Experimental);
+    let field = |name: &str| player.fields.iter().find(|v| v.name == name).unwrap();
+    assert_eq!(field("health").stability, Stability::Stable);
+    assert_eq!(field("entity_id").stability, Stability::Internal);
+
+    let settings = record::<Settings>();
+    assert_eq!(settings.fields[2].since.as_deref(), Some("1.1.0"));
+    assert_eq!(settings.fields[2].stability, Stability::Experimental);
+
+    let walker = TypeWalker::new()
+        .process_type::<Player>()
+        .process_type::<Debugger>()
+        .process_type::<Settings>()
+        .process_type::<Weather>()
+        .document_global_instance::<Export>()?;
+    assert_eq!(
+        walker.global_instances_off[0].since.as_deref(),
+        Some("1.0.0")
+    );
+    let json = walker.to_json().unwrap();
+    let walker: TypeWalker = serde_json::from_str(&json).unwrap();
+    let instances = |walker: &TypeWalker| {
+        walker
+            .global_instances_off
+            .iter()
+            .map(|v| v.name.clone())
+            .collect::<Vec<_>>()
+    };
+
+    let experimental = walker.clone().filter_stability(Stability::Experimental);
+    assert_eq!(experimental.given_types.len(), 3);
+    let player = experimental.given_types[0].record().unwrap();
+    assert!(player.methods.iter().any(|v| v.name == "fly"));
+    assert!(!player.fields.iter().any(|v| v.name == "entity_id"));
+    assert!(!instances(&experimental).contains(&"debugger".to_owned()));
+    assert!(instances(&experimental).contains(&"player".to_owned()));
+
+    let stable = walker.filter_stability(Stability::Stable);
+    let player = stable.given_types[0].record().unwrap();
+    assert!(!player.methods.iter().any(|v| v.name == "fly"));
+    assert!(!player.documentation.keys().any(|v| v == &"fly"));
+    let settings = stable.given_types[1].record().unwrap();
+    assert!(!settings.fields.iter().any(|v| v.name == "render_distance"));
+    assert_eq!(stable.given_types.len(), 2);
+
+    let lua = Lua::new();
+    tealr::mlu::set_global_env(Export, &lua)?;
+    let help: String = lua
+        .load("return game.player.help(\"fly\") .. game.player.help(\"teleport\")")
+        .set_name("stability")
+        .eval()?;
+    assert!(help.contains("stability: experimental"));
+    assert!(help.contains("since: 1.3.0"));
+    Ok(())
+}
+
+#[test]
+fn help() -> Result<()> {
+    let lua = Lua::new();
+    tealr::mlu::set_global_env(Export, &lua)?;
+
+    let pages: String = lua.load("return game.player.help()").eval()?;
+    assert!(pages.starts_with("A player that is currently online"));
+    assert_eq!(pages.matches("health").count(), 1);
+    assert!(pages.contains("__tostring"));
+
+    let page: String = lua.load("return game.player.help('health')").eval()?;
+    assert!(page.starts_with("integer"));
+    assert!(page.contains("The amount of health the player has left"));
+
+    let page: String = lua.load("return game.player.help('tele')").eval()?;
+    assert!(page.contains("teleport (method)"));
+    let page: String = lua.load("return game.player.help('sndmsg')").eval()?;
+    assert!(page.contains("send_message (method)"));
+    let page: String = lua.load("return game.player.help('chat')").eval()?;
+    assert!(page.contains("send_message (method)"));
+    assert!(!page.contains("teleport"));
+    let page: String = lua.load("return game.player.help('xyz')").eval()?;
+    assert!(page.contains("not found"));
+
+    let entries: Vec<HashMap<String, String>> =
+        lua.load("return game.player.help_entries('tele')").eval()?;
+    assert_eq!(entries[0]["name"], "teleport");
+    assert_eq!(entries[0]["kind"], "method");
+    assert!(entries[0]["doc"].contains("Moves the player **instantly**"));
+    let entries: Vec<HashMap<String, String>> =
+        lua.load("return game.player.help_entries()").eval()?;
+    assert!(entries
+        .iter()
+        .any(|v| v["name"] == "__tostring" && v["kind"] == "meta method"));
+
+    let page: String = lua.load("return help(game.player, 'teleport')").eval()?;
+    assert!(page.contains("Moves the player **instantly**"));
+    let page: String = lua.load("return help('game.player.teleport')").eval()?;
+    assert!(page.contains("Moves the player **instantly**"));
+    let page: String = lua.load("return help('game.player')").eval()?;
+    assert!(page.contains("Available pages"));
+    let page: String = lua.load("return help(12)").eval()?;
+    assert_eq!(page, "No documentation available for this integer");
+    Ok(())
+}
+
+#[test]
+fn help_without_teal_data() -> Result<()> {
+    let lua = Lua::new();
+    lua.globals().set("counter", ManualCounter {})?;
+    let page: String = lua.load("return counter.help('count')").eval()?;
+    assert!(page.contains("Counts to the given number"));
+    let page: String = lua.load("return counter.help('max')").eval()?;
+    assert!(page.contains("The highest number it counts to"));
+    let page: String = lua.load("return counter.help('\\255max')").eval()?;
+    assert!(page.contains("not found"));
+    Ok(())
+}
+
+#[test]
+fn type_info() -> Result<()> {
+    let lua = Lua::new();
+    tealr::mlu::set_global_env(Export, &lua)?;
+    let code = "
+        local t = game.player.__tealr_type()
+        local names = {}
+        for _, method in ipairs(t.methods) do
+            table.insert(names, method.name)
+        end
+        return t.type_doc, table.concat(names, ','), #t.functions, t.fields[1].name
+    ";
+    let (doc, methods, functions, field): (String, String, i64, String) = lua.load(code).eval()?;
+    assert!(doc.starts_with("A player that is currently online"));
+    assert_eq!(methods, "teleport,move_to,send_message,fly");
+    //spawn, help, help_entries and __tealr_type itself
+    assert_eq!(functions, 4);
+    assert_eq!(field, "health");
+
+    let method: String = lua
+        .load("return debugger.__tealr_type().methods[1].name")
+        .eval()?;
+    assert_eq!(method, "pause");
+    assert!(record::<Debugger>()
+        .functions
+        .iter()
+        .any(|v| v.name == "__tealr_type"));
+    Ok(())
+}
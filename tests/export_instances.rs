@@ -1,8 +1,11 @@
 use mlua::IntoLua;
 use tealr::{
     create_union_mlua,
-    mlu::{mlua::FromLua, TealData, TealDataMethods, TypedFunction, UserData},
-    ToTypename, TypeWalker,
+    mlu::{
+        mlua::{FromLua, Lua},
+        SandboxBuilder, TealData, TealDataFields, TealDataMethods, TypedFunction, UserData,
+    },
+    type_to_string, GlobalInstance, ToTypename, TypeBody, TypeWalker,
 };
 
 create_union_mlua!(enum X = String | f32 | bool);
@@ -75,3 +78,275 @@ fn test_limited() {
     let res: bool = lua.load(code).eval().unwrap();
     assert!(res);
 }
+
+#[test]
+fn reads_json_of_previous_versions() {
+    let walker: TypeWalker =
+        serde_json::from_str(include_str!("export_instances_previous.json")).unwrap();
+    assert_eq!(walker.global_instances_off[1].full_name(), "example_a");
+    assert!(walker.modules.is_empty());
+    let record = walker.given_types[0].record().unwrap();
+    assert!(record.field_functions.is_empty());
+    assert!(record.methods[0].deprecated.is_none());
+}
+
+#[derive(Clone, UserData, ToTypename)]
+struct Server {}
+
+impl TealData for Server {
+    fn add_methods<T: TealDataMethods<Self>>(methods: &mut T) {
+        methods.add_method("double", |_, _, x: i64| Ok(x * 2));
+    }
+    fn add_fields<F: TealDataFields<Self>>(fields: &mut F) {
+        fields.document("The maximum amount of players");
+        fields.add_constant("MAX_PLAYERS", 64);
+        fields.add_constant("NAME", "main \"server\"");
+    }
+}
+
+tealr::mlua_create_named_parameters!(
+    AddParams with
+        left : i64,
+        right : i64,
+);
+
+//exports every kind of instance, both at the top level and in namespaces
+#[derive(Default)]
+struct Game;
+impl tealr::mlu::ExportInstances for Game {
+    fn add_instances<T: tealr::mlu::InstanceCollector>(
+        self,
+        instance_collector: &mut T,
+    ) -> mlua::Result<()> {
+        let mut counter = 0;
+        instance_collector
+            .document_instance("The server that is running")
+            .add_instance("server", |_| Ok(Server {}))?
+            .document_instance("adds two numbers together")
+            .add_function("add", |_, params: AddParams| Ok(params.left + params.right))?
+            .document_instance("counts how often it got called")
+            .add_function_mut("count", move |_, ()| {
+                counter += 1;
+                Ok(counter)
+            })?
+            .document_instance("The version of the api")
+            .add_constant("VERSION", "1.2.0")?
+            .document_instance("casts a ray")
+            .add_instance("game.physics.raycast", |lua| {
+                TypedFunction::from_rust(|_, distance: f64| Ok(distance > 10.0), lua)
+            })?
+            .add_instance("game.physics.gravity", |_| Ok(9.81))?
+            .add_instance("game.version", |_| Ok(String::from("1.0")))?
+            .add_constant("limits.MAX_PLAYERS", 64)?;
+        #[cfg(feature = "mlua_async")]
+        instance_collector
+            .add_async_function("async_double", |_, x: i64| async move { Ok(x * 2) })?;
+        Ok(())
+    }
+}
+
+fn instance<'a>(instances: &'a [GlobalInstance], full_name: &str) -> &'a GlobalInstance {
+    instances
+        .iter()
+        .find(|v| v.full_name() == full_name)
+        .unwrap_or_else(|| panic!("`{full_name}` is not documented"))
+}
+
+#[test]
+fn global_instances() -> mlua::Result<()> {
+    let walker = TypeWalker::new().document_global_instance::<Game>()?;
+    let instances = &walker.global_instances_off;
+
+    let add = instance(instances, "add");
+    assert_eq!(add.doc, "adds two numbers together\n");
+    assert_eq!(
+        type_to_string(&add.ty, false),
+        "function(left:integer , right:integer):(integer)"
+    );
+    assert_eq!(
+        instance(instances, "count").doc,
+        "counts how often it got called\n"
+    );
+    assert_eq!(instance(instances, "server").value, None);
+
+    let version = instance(instances, "VERSION");
+    assert_eq!(version.value.as_deref(), Some("\"1.2.0\""));
+    assert_eq!(version.doc, "The version of the api\n");
+    assert_eq!(
+        instance(instances, "limits.MAX_PLAYERS").value.as_deref(),
+        Some("64")
+    );
+
+    let raycast = instance(instances, "game.physics.raycast");
+    assert_eq!(raycast.name, "raycast");
+    assert_eq!(raycast.namespace, ["game", "physics"]);
+    assert_eq!(raycast.doc, "casts a ray\n");
+    assert_eq!(instance(instances, "game.version").namespace, ["game"]);
+    assert!(add.namespace.is_empty());
+
+    let lua = Lua::new();
+    tealr::mlu::set_global_env(Game, &lua)?;
+    let code = "
+        assert(game.physics.gravity == 9.81)
+        assert(game.version == \"1.0\")
+        assert(game.physics.raycast(12))
+        assert(VERSION == \"1.2.0\")
+        assert(limits.MAX_PLAYERS == 64)
+        assert(server.NAME == 'main \"server\"')
+        assert(server.MAX_PLAYERS == 64)
+        count()
+        return add(count(), 3)
+    ";
+    let res: i64 = lua.load(code).set_name("global_instances").eval()?;
+    assert_eq!(res, 5);
+
+    #[cfg(feature = "mlua_async")]
+    {
+        assert!(instances.iter().any(|v| v.name == "async_double"));
+        let thread = lua.create_thread(lua.globals().get("async_double")?)?;
+        let res: i64 = thread.resume(21)?;
+        assert_eq!(res, 42);
+    }
+    Ok(())
+}
+
+#[test]
+fn constants() {
+    let body = Server::get_type_body();
+    let record = body.record().unwrap();
+    assert_eq!(record.fields[0].name, "MAX_PLAYERS");
+    assert_eq!(record.fields[0].value.as_deref(), Some("64"));
+    assert_eq!(
+        record.fields[1].value.as_deref(),
+        Some("\"main \\\"server\\\"\"")
+    );
+    assert_eq!(
+        record.documentation.get(&record.fields[0].name).unwrap(),
+        "The maximum amount of players"
+    );
+}
+
+#[derive(Default)]
+struct Clashing;
+impl tealr::mlu::ExportInstances for Clashing {
+    fn add_instances<T: tealr::mlu::InstanceCollector>(
+        self,
+        instance_collector: &mut T,
+    ) -> mlua::Result<()> {
+        instance_collector
+            .add_instance("game", |_| Ok(1))?
+            .add_instance("game.physics", |_| Ok(2))?;
+        Ok(())
+    }
+}
+
+#[test]
+fn namespaces() -> mlua::Result<()> {
+    let walker = TypeWalker::new().document_global_instance::<Game>()?;
+    let index = &walker.to_markdown()[0].content;
+    let headings = index
+        .lines()
+        .filter(|v| v.starts_with('#'))
+        .collect::<Vec<_>>();
+    let nested = headings
+        .iter()
+        .position(|v| *v == "### game")
+        .expect("no heading for the namespace");
+    assert!(headings[..nested].contains(&"### add"));
+    assert_eq!(
+        headings[nested..nested + 7],
+        [
+            "### game",
+            "#### game.version",
+            "#### game.physics",
+            "##### game.physics.raycast",
+            "##### game.physics.gravity",
+            "### limits",
+            "#### limits.MAX\\_PLAYERS",
+        ]
+    );
+
+    let lua = Lua::new();
+    assert!(tealr::mlu::set_global_env(Clashing, &lua).is_err());
+    Ok(())
+}
+
+#[test]
+fn modules() -> mlua::Result<()> {
+    let walker = TypeWalker::new()
+        .process_type::<Server>()
+        .document_module::<Game>("game.api")?;
+    assert!(walker.global_instances_off.is_empty());
+    assert_eq!(walker.modules.len(), 1);
+    let module = &walker.modules[0];
+    assert_eq!(module.name, "game.api");
+    assert_eq!(module.instances[0].name, "server");
+    assert_eq!(module.instances[0].doc, "The server that is running\n");
+
+    let lua = Lua::new();
+    tealr::mlu::set_module_env("game.api", Game, &lua)?;
+    let code = "
+        assert(server == nil)
+        local api = require(\"game.api\")
+        assert(api == require(\"game.api\"))
+        assert(api.game.physics.raycast(12))
+        return api.server:double(api.add(2, 1))
+    ";
+    let res: i64 = lua.load(code).set_name("modules").eval()?;
+    assert_eq!(res, 6);
+    Ok(())
+}
+
+#[test]
+fn sandbox() -> mlua::Result<()> {
+    let builder = SandboxBuilder::new().deny("string.rep").allow("os.time");
+
+    let lua = Lua::new();
+    let env = builder.build(Game, &lua)?;
+    let code = "
+        assert(io == nil)
+        assert(load == nil)
+        assert(require == nil)
+        assert(os.execute == nil)
+        assert(type(os.time()) == \"number\")
+        assert(string.rep == nil)
+        -- strings share their metatable with the rest of the lua state, which the sandbox leaves alone
+        assert((\"x\"):rep(3) == \"xxx\")
+        assert((\"x\"):upper() == \"X\")
+        string.upper = nil
+        return string.format(\"%s %s\", game.version, tostring(game.physics.raycast(12)))
+    ";
+    let res: String = lua
+        .load(code)
+        .set_name("sandbox")
+        .set_environment(env)
+        .eval()?;
+    assert_eq!(res, "1.0 true");
+    let upper: String = lua.load("return string.upper(\"still here\")").eval()?;
+    assert_eq!(upper, "STILL HERE");
+
+    let walker = builder.document::<Game>(TypeWalker::new(), &lua)?;
+    let std_lib = walker
+        .available_std_lib
+        .as_deref()
+        .expect("no std lib documented");
+    assert!(std_lib.iter().any(|v| v == "string.format"));
+    assert!(std_lib.iter().any(|v| v == "os.time"));
+    assert!(std_lib.iter().any(|v| v == "pcall"));
+    assert!(!std_lib.iter().any(|v| v == "string.rep"));
+    assert!(!std_lib.iter().any(|v| v == "os.execute"));
+    assert!(!std_lib.iter().any(|v| v.starts_with("io")));
+    assert!(!std_lib.iter().any(|v| v.starts_with("game")));
+    instance(&walker.global_instances_off, "game.physics.raycast");
+    let index = &walker.to_markdown()[0].content;
+    assert!(index.contains("## Standard library"));
+    assert!(index.contains("- `os.time`"));
+
+    let env = SandboxBuilder::new()
+        .allow("os.time")
+        .deny("os")
+        .build(Game, &lua)?;
+    let os: Option<mlua::Table> = env.get("os")?;
+    assert!(os.is_none());
+    Ok(())
+}
@@ -2,8 +2,11 @@ use std::collections::HashMap;
 
 use tealr::{
     create_generic_mlua,
-    mlu::{mlua::FromLua, TealData, TealDataMethods, TypedFunction, UserData},
-    ToTypename, TypeWalker,
+    mlu::{
+        mlua::{FromLua, Lua, Value},
+        FromLuaExact, FromToLua, TealData, TealDataMethods, TypedFunction, UserData,
+    },
+    type_to_string, ExportedFunction, ToTypename, TypeGenerator, TypeWalker, Union,
 };
 
 create_generic_mlua!(X);
@@ -49,3 +52,95 @@ fn make_generic() {
 
     assert_eq!(new_value, old_value);
 }
+
+#[derive(Clone, Debug, PartialEq, FromToLua, ToTypename, FromLuaExact)]
+struct Point {
+    x: i64,
+    y: i64,
+}
+
+#[derive(Union)]
+enum PointOrName {
+    Point(Point),
+    Name(String),
+}
+
+create_generic_mlua!(P: Point);
+create_generic_mlua!(N: PointOrName);
+
+#[derive(Clone, UserData, ToTypename)]
+struct Bounded {}
+
+impl TealData for Bounded {
+    fn add_methods<T: TealDataMethods<Self>>(methods: &mut T) {
+        methods.add_function("identity", |_, x: P| Ok(x));
+        methods.add_function("named", |_, (x, y): (N, X)| Ok((x, y)));
+    }
+}
+
+fn method<'a>(walker: &'a TypeWalker, name: &str) -> &'a ExportedFunction {
+    walker
+        .given_types
+        .iter()
+        .find_map(|v| match v {
+            TypeGenerator::Record(x) if x.ty == Bounded::to_typename() => Some(x),
+            _ => None,
+        })
+        .expect("a record for Bounded")
+        .functions
+        .iter()
+        .find(|v| v.name == name)
+        .expect("the method")
+}
+
+#[test]
+fn checked_on_conversion() -> tealr::mlu::mlua::Result<()> {
+    let lua = Lua::new();
+    lua.globals()
+        .set("Bounded", tealr::mlu::UserDataProxy::<Bounded>::new(&lua)?)?;
+    let point: Value = lua.load("return Bounded.identity({x = 1, y = 2})").eval()?;
+    assert!(point.is_table());
+    lua.load("assert(Bounded.named('a', 1) == 'a')").exec()?;
+    lua.load("assert(Bounded.named({x = 1, y = 2}, nil).x == 1)")
+        .exec()?;
+
+    let error = lua
+        .load("return Bounded.identity({x = 1})")
+        .exec()
+        .unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("value does not satisfy the bound `Point`"),
+        "{}",
+        error
+    );
+    assert!(lua.load("return Bounded.named(1, 1)").exec().is_err());
+    Ok(())
+}
+
+#[test]
+fn signatures() {
+    let walker = TypeWalker::new().process_type::<Bounded>();
+    assert_eq!(
+        method(&walker, "identity").to_lua_ls_generics().unwrap(),
+        "---@generic P: Point"
+    );
+    assert_eq!(
+        method(&walker, "named").to_lua_ls_generics().unwrap(),
+        "---@generic N: (Point | string), X"
+    );
+    assert_eq!(
+        type_to_string(&method(&walker, "identity").into_type(), false),
+        "function<P is Point>(P):(P)"
+    );
+
+    let json = walker.to_json().unwrap();
+    let walker: TypeWalker = serde_json::from_str(&json).unwrap();
+    let bound = method(&walker, "identity").params[0]
+        .ty
+        .single()
+        .and_then(|v| v.bound.as_deref())
+        .cloned();
+    assert_eq!(bound, Some(Point::to_typename()));
+}
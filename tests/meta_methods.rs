@@ -1,7 +1,13 @@
+use std::collections::HashMap;
+
 use mlua::MetaMethod;
 use tealr::{
-    mlu::{TealData, TealDataMethods, UserData},
-    ToTypename, TypeWalker,
+    mlu::{
+        mlua::{Lua, Value},
+        user_data_proxy::UserDataProxy,
+        TealData, TealDataFields, TealDataMethods, UserData,
+    },
+    type_to_string, ExportedFunction, Placement, ToTypename, Type, TypeBody, TypeWalker,
 };
 
 #[derive(Clone, UserData, ToTypename)]
@@ -40,3 +46,358 @@ fn test() {
 
     assert_eq!(new_value, old_value);
 }
+
+#[derive(Clone, UserData, ToTypename)]
+#[tealr(proxy_name = "Vectors")]
+struct Vector {
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+impl Vector {
+    fn new(x: f64) -> Self {
+        Vector { x, y: 0.0, z: 0.0 }
+    }
+}
+
+impl TealData for Vector {
+    fn add_methods<T: TealDataMethods<Self>>(methods: &mut T) {
+        methods.document("Creates a new vector");
+        methods.add_constructor(|_, (x, y, z): (f64, f64, f64)| Ok(Vector { x, y, z }));
+        methods.add_method("length", |_, this, ()| {
+            Ok((this.x * this.x + this.y * this.y + this.z * this.z).sqrt())
+        });
+        methods.add_meta_method(MetaMethod::Add, |_, this, other: f64| {
+            Ok(Vector::new(this.x + other))
+        });
+        methods.add_meta_method(MetaMethod::Unm, |_, this, ()| Ok(Vector::new(-this.x)));
+        methods.add_meta_method(MetaMethod::Len, |_, _, ()| Ok(3));
+        methods.add_meta_method(MetaMethod::ToString, |_, _, ()| Ok("vector"));
+        methods.placement(Placement::Proxy);
+        methods.add_function("from_x", |_, x: f64| Ok(Vector::new(x)));
+        methods.placement(Placement::Instance);
+        methods.add_function("dimensions", |_, ()| Ok(3));
+        methods.add_function("zero", |_, ()| Ok(Vector::new(0.0)));
+        methods.generate_help();
+    }
+    fn add_fields<F: TealDataFields<Self>>(fields: &mut F) {
+        fields.add_field_method_get("x", |_, this| Ok(this.x));
+        fields.add_field_function_get("unit", |_, _| Ok(Vector::new(1.0)));
+        fields.add_meta_field_with(MetaMethod::Type, |_| Ok("Vector"));
+        fields.placement(Placement::Proxy);
+        fields.add_constant("ORIGIN", 0);
+    }
+}
+
+#[derive(Clone, UserData, ToTypename)]
+struct Broken {}
+
+impl TealData for Broken {
+    fn add_methods<T: TealDataMethods<Self>>(methods: &mut T) {
+        methods.add_meta_method(MetaMethod::Eq, |_, _, _other: Value| Ok(1));
+        methods.add_meta_method(MetaMethod::NewIndex, |_, _, _key: String| Ok(1));
+    }
+}
+
+#[derive(Clone, UserData, ToTypename)]
+struct Plain {}
+
+impl TealData for Plain {}
+
+fn names<'a>(members: impl Iterator<Item = &'a tealr::NameContainer>) -> Vec<String> {
+    members.map(ToString::to_string).collect()
+}
+
+fn with_vector(lua: &Lua) -> mlua::Result<()> {
+    lua.globals()
+        .set("Vector", UserDataProxy::<Vector>::new(lua)?)
+}
+
+#[test]
+fn rendering() {
+    let record = Vector::get_type_body();
+    let record = record.record().unwrap();
+    let operators = record
+        .meta_method
+        .iter()
+        .map(|v| v.to_lua_ls_operator())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        operators,
+        [
+            Some("---@operator add(number): Vector".to_owned()),
+            Some("---@operator unm: Vector".to_owned()),
+            Some("---@operator len: integer".to_owned()),
+            None
+        ]
+    );
+    assert_eq!(
+        record.meta_method[0].to_teal_metamethod().unwrap(),
+        "metamethod __add: function(self:Vector , number):(Vector)"
+    );
+    assert!(record.meta_method_warnings().is_empty());
+
+    let pages = TypeWalker::new().process_type::<Vector>().to_markdown();
+    assert!(pages[1].content.contains("metamethod \\_\\_add: function"));
+}
+
+#[test]
+fn warnings() {
+    let warnings = TypeWalker::new()
+        .process_type::<Vector>()
+        .process_type::<Broken>()
+        .meta_method_warnings();
+    assert_eq!(
+        warnings,
+        [
+            "Broken: `__eq` should return a single boolean, but returns integer",
+            "Broken: `__newindex` gets called with 3 values, but takes 2",
+            "Broken: the values returned by `__newindex` are ignored, but it returns integer",
+        ]
+    );
+}
+
+#[test]
+fn known_meta_fields() {
+    for name in ["__gc", "__mode", "__name", "__metatable", "__type"] {
+        let function = ExportedFunction::new::<(), (), _>(name, true, None);
+        assert!(function.meta_method_warnings().is_empty(), "{name}");
+    }
+    let function = ExportedFunction::new::<(), (), _>("__typo", true, None);
+    assert_eq!(
+        function.meta_method_warnings(),
+        ["`__typo` is not a meta method lua knows about"]
+    );
+}
+
+#[test]
+fn constructor() -> mlua::Result<()> {
+    let body = UserDataProxy::<Vector>::get_type_body();
+    let record = body.record().unwrap();
+    let constructor = record.constructor.as_ref().unwrap();
+    assert_eq!(constructor.name, "new");
+    assert_eq!(
+        type_to_string(&constructor.into_type(), false),
+        "function(number , number , number):(Vector)"
+    );
+    let call = &record.meta_function[0];
+    assert_eq!(call.name, "__call");
+    assert_eq!(
+        call.to_teal_metamethod().unwrap(),
+        "metamethod __call: function(self:Vectors , number , number , number):(Vector)"
+    );
+
+    let instance = Vector::get_type_body();
+    let instance = instance.record().unwrap();
+    assert!(instance.constructor.is_some());
+    assert!(instance.meta_function.is_empty());
+
+    let pages = TypeWalker::new()
+        .process_type::<UserDataProxy<Vector>>()
+        .to_markdown();
+    let page = &pages[1].content;
+    assert!(page.contains("## Constructor\n\n### new"));
+    assert!(page.contains("Creates a new vector"));
+    assert!(!page.contains("__call"));
+
+    let lua = Lua::new();
+    with_vector(&lua)?;
+    let code = "return Vector(2, 3, 6):length(), Vector.new(0, 3, 4):length()";
+    let (called, new): (f64, f64) = lua.load(code).eval()?;
+    assert_eq!((called, new), (7.0, 5.0));
+    let instance_call = "return pcall(function() return Vector.new(1, 2, 3)(1, 2, 3) end)";
+    let callable: bool = lua.load(instance_call).eval()?;
+    assert!(!callable);
+
+    let help: String = lua.load("return Vector.help('new')").eval()?;
+    assert!(help.contains("Creates a new vector"));
+    let code = "local kinds = {} for _, v in ipairs(Vector.help_entries()) do kinds[v.name] = v.kind end return kinds";
+    let kinds: HashMap<String, String> = lua.load(code).eval()?;
+    assert_eq!(kinds["new"], "constructor");
+    assert!(!kinds.contains_key("__call"));
+    Ok(())
+}
+
+#[test]
+fn proxy_name() {
+    assert_eq!(
+        type_to_string(&UserDataProxy::<Vector>::to_typename(), false),
+        "Vectors"
+    );
+    assert_eq!(
+        type_to_string(&UserDataProxy::<Plain>::to_typename(), false),
+        "ClassPlain"
+    );
+}
+
+#[test]
+fn proxy_members() -> mlua::Result<()> {
+    let walker = TypeWalker::new()
+        .process_type::<Vector>()
+        .process_type::<UserDataProxy<Vector>>();
+    let instance = walker.given_types[0].record().unwrap();
+    let proxy = walker.given_types[1].record().unwrap();
+
+    assert_eq!(type_to_string(&proxy.ty, false), "Vectors");
+    assert_eq!(
+        names(proxy.functions.iter().map(|v| &v.name)),
+        ["new", "from_x", "zero", "help", "help_entries"]
+    );
+    assert!(proxy.methods.is_empty());
+    assert_eq!(
+        names(proxy.field_functions.iter().map(|v| &v.name)),
+        ["unit"]
+    );
+    assert_eq!(names(proxy.fields.iter().map(|v| &v.name)), ["ORIGIN"]);
+
+    assert_eq!(
+        names(instance.functions.iter().map(|v| &v.name)),
+        ["new", "dimensions", "zero", "help", "help_entries"]
+    );
+    assert_eq!(names(instance.fields.iter().map(|v| &v.name)), ["x"]);
+    assert_eq!(
+        names(instance.field_functions.iter().map(|v| &v.name)),
+        ["unit"]
+    );
+    assert_eq!(
+        names(instance.static_fields.iter().map(|v| &v.name)),
+        [MetaMethod::Type.name()]
+    );
+
+    let lua = Lua::new();
+    with_vector(&lua)?;
+    let code = "return Vector.from_x(-3):length(), Vector.unit.x, Vector.ORIGIN";
+    let res: (f64, f64, i64) = lua.load(code).eval()?;
+    assert_eq!(res, (3.0, 1.0, 0));
+    Ok(())
+}
+
+#[derive(Clone, UserData, ToTypename)]
+struct Inventory(Vec<String>);
+
+impl TealData for Inventory {
+    fn add_methods<T: TealDataMethods<Self>>(methods: &mut T) {
+        methods.document("Loops over every item, with its slot");
+        methods.add_iterator(|this| {
+            this.0
+                .clone()
+                .into_iter()
+                .enumerate()
+                .map(|(k, v)| (k as i64 + 1, v))
+        });
+    }
+}
+
+//how a loop over the inventory is written, which depends on what the lua version supports
+#[cfg(feature = "mlua_luau")]
+const LOOP: &str = "inventory";
+#[cfg(any(
+    feature = "mlua_lua54",
+    feature = "mlua_lua53",
+    feature = "mlua_lua52",
+    feature = "mlua_luajit52"
+))]
+const LOOP: &str = "pairs(inventory)";
+#[cfg(not(any(
+    feature = "mlua_luau",
+    feature = "mlua_lua54",
+    feature = "mlua_lua53",
+    feature = "mlua_lua52",
+    feature = "mlua_luajit52"
+)))]
+const LOOP: &str = "inventory:pairs()";
+
+#[test]
+fn iterator() -> mlua::Result<()> {
+    let lua = Lua::new();
+    lua.globals().set(
+        "inventory",
+        Inventory(vec!["sword".to_owned(), "shield".to_owned()]),
+    )?;
+    let code = format!(
+        "
+        local items = {{}}
+        for slot, item in {LOOP} do
+            table.insert(items, slot .. '=' .. item)
+        end
+        -- every loop gets a fresh iterator
+        for slot, item in {LOOP} do
+            table.insert(items, slot .. '=' .. item)
+        end
+        return table.concat(items, ',')
+    "
+    );
+    let items: String = lua.load(code).eval()?;
+    assert_eq!(items, "1=sword,2=shield,1=sword,2=shield");
+
+    let body = Inventory::get_type_body();
+    let record = body.record().unwrap();
+    #[cfg(feature = "mlua_luau")]
+    let (pairs, name) = (&record.meta_method[0], "__iter");
+    #[cfg(any(
+        feature = "mlua_lua54",
+        feature = "mlua_lua53",
+        feature = "mlua_lua52",
+        feature = "mlua_luajit52"
+    ))]
+    let (pairs, name) = (&record.meta_method[0], "__pairs");
+    #[cfg(not(any(
+        feature = "mlua_luau",
+        feature = "mlua_lua54",
+        feature = "mlua_lua53",
+        feature = "mlua_lua52",
+        feature = "mlua_luajit52"
+    )))]
+    let (pairs, name) = (&record.methods[0], "pairs");
+    assert_eq!(pairs.name, name);
+    assert_eq!(
+        type_to_string(&pairs.returns[0], false),
+        "function():(integer , string)"
+    );
+    assert!(record.meta_method_warnings().is_empty());
+    Ok(())
+}
+
+#[derive(Clone, UserData, ToTypename)]
+struct Entity {
+    id: i64,
+    components: HashMap<String, i64>,
+}
+
+impl TealData for Entity {
+    fn add_methods<T: TealDataMethods<Self>>(methods: &mut T) {
+        methods.add_method("component_count", |_, this, ()| Ok(this.components.len()));
+        methods.add_dynamic_index(|_, this, key: String| Ok(this.components.get(&key).copied()));
+    }
+    fn add_fields<F: TealDataFields<Self>>(fields: &mut F) {
+        fields.add_field_method_get("id", |_, this| Ok(this.id));
+    }
+}
+
+#[test]
+fn dynamic_index() -> mlua::Result<()> {
+    let lua = Lua::new();
+    lua.globals().set(
+        "entity",
+        Entity {
+            id: 7,
+            components: [("health".to_owned(), 20)].into_iter().collect(),
+        },
+    )?;
+    let code = "return entity.id, entity:component_count(), entity.health, entity.mana";
+    let (id, count, health, mana): (i64, i64, i64, Option<i64>) = lua.load(code).eval()?;
+    assert_eq!((id, count, health, mana), (7, 1, 20, None));
+
+    let body = Entity::get_type_body();
+    let record = body.record().unwrap();
+    let index = record.dynamic_index.clone().unwrap();
+    assert_eq!(
+        type_to_string(&Type::Map(index), false),
+        "{string : integer}"
+    );
+    assert_eq!(record.meta_method[0].name, "__index");
+    assert_eq!(record.meta_method[0].to_lua_ls_operator(), None);
+    assert_eq!(record.fields[0].name, "id");
+    Ok(())
+}
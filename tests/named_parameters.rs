@@ -3,7 +3,7 @@ use tealr::{
         mlua::{FromLua, Lua, Result},
         TealData, TealDataMethods, UserData,
     },
-    ToTypename, TypeWalker,
+    ToTypename, TypeBody, TypeWalker,
 };
 #[derive(Clone, UserData, ToTypename)]
 struct Example {}
@@ -62,3 +62,60 @@ fn main() -> Result<()> {
     assert_eq!(field2, 3);
     Ok(())
 }
+
+#[derive(Clone, UserData, ToTypename)]
+struct Calculator {}
+
+impl TealData for Calculator {
+    fn add_methods<T: TealDataMethods<Self>>(methods: &mut T) {
+        methods
+            .document("Divides two numbers")
+            .document_param("left", "the number to divide")
+            .document_param("right", "the number to divide by")
+            .document_return("the result of the division")
+            .document_error("when `right` is 0")
+            .document_example("local result = calculator:divide(10, 2)");
+        methods.add_method("divide", |_, _, (left, right): (i64, i64)| {
+            if right == 0 {
+                return Err(mlua::Error::RuntimeError("division by 0".into()));
+            }
+            Ok(left / right)
+        });
+        methods.add_method("undocumented", |_, _, ()| Ok(()));
+        methods.generate_help();
+    }
+}
+
+#[test]
+fn function_docs() -> Result<()> {
+    let body = Calculator::get_type_body();
+    let record = body.record().unwrap();
+    let divide = &record.methods[0];
+    assert_eq!(divide.docs.params[0].name, "left");
+    assert_eq!(divide.docs.params[1].doc, "the number to divide by");
+    assert_eq!(divide.docs.returns, ["the result of the division"]);
+    assert_eq!(divide.docs.errors, ["when `right` is 0"]);
+    assert_eq!(
+        divide.docs.examples,
+        ["local result = calculator:divide(10, 2)"]
+    );
+    assert!(record.methods[1].docs.is_empty());
+
+    let json = serde_json::to_value(divide).unwrap();
+    assert_eq!(json["docs"]["params"][0]["doc"], "the number to divide");
+
+    let lua = Lua::new();
+    lua.globals().set("calculator", Calculator {})?;
+    let help: String = lua
+        .load("return calculator.help(\"divide\")")
+        .set_name("function_docs")
+        .eval()?;
+    assert!(help.contains("left: the number to divide"));
+    assert!(help.contains("the result of the division"));
+    assert!(help.contains("when `right` is 0"));
+    let help: String = lua
+        .load("return calculator.help(\"undocumented\")")
+        .eval()?;
+    assert!(!help.contains("params:"));
+    Ok(())
+}
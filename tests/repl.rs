@@ -0,0 +1,122 @@
+use tealr::{
+    mlu::{
+        mlua::{Lua, MetaMethod, Result},
+        TealData, TealDataFields, TealDataMethods, UserData,
+    },
+    repl::{CompletionKind, Repl},
+    ToTypename, TypeWalker,
+};
+
+#[derive(Clone, UserData, ToTypename)]
+struct Vector {}
+
+impl TealData for Vector {
+    fn add_methods<T: TealDataMethods<Self>>(methods: &mut T) {
+        methods.document("The length of the vector");
+        methods.add_method("length", |_, _, ()| Ok(1.0));
+        methods.add_method("normalized", |_, _, ()| Ok(Vector {}));
+    }
+    fn add_fields<F: TealDataFields<Self>>(fields: &mut F) {
+        fields.add_field_method_get("x", |_, _| Ok(0.0));
+        fields.add_field_method_get("y", |_, _| Ok(0.0));
+        fields.add_field_function_get("unit", |_, _| Ok(Vector {}));
+        fields.add_meta_field_with(MetaMethod::Type, |_| Ok("Vector"));
+    }
+}
+
+#[derive(Clone, UserData, ToTypename)]
+struct Player {}
+
+impl TealData for Player {
+    fn add_methods<T: TealDataMethods<Self>>(methods: &mut T) {
+        methods.add_method("get_position", |_, _, ()| Ok(Vector {}));
+        methods.add_method("get_name", |_, _, ()| Ok("steve"));
+    }
+    fn add_fields<F: TealDataFields<Self>>(fields: &mut F) {
+        fields.add_field_method_get("velocity", |_, _| Ok(Vector {}));
+    }
+}
+
+#[derive(Default)]
+struct Export;
+impl tealr::mlu::ExportInstances for Export {
+    fn add_instances<T: tealr::mlu::InstanceCollector>(
+        self,
+        instance_collector: &mut T,
+    ) -> mlua::Result<()> {
+        instance_collector
+            .document_instance("The player that is currently playing")
+            .add_instance("game.player", |_| Ok(Player {}))?
+            .add_function("spawn", |_, ()| Ok(Player {}))?;
+        Ok(())
+    }
+}
+
+fn repl() -> Result<Repl> {
+    let lua = Lua::new();
+    tealr::mlu::set_global_env(Export, &lua)?;
+    let walker = TypeWalker::new()
+        .process_type::<Player>()
+        .process_type::<Vector>()
+        .document_global_instance::<Export>()?;
+    Ok(Repl::new(lua, walker))
+}
+
+fn names(repl: &Repl, line: &str) -> Vec<String> {
+    repl.complete(line)
+        .candidates
+        .into_iter()
+        .map(|v| v.name)
+        .collect()
+}
+
+#[test]
+fn eval() -> Result<()> {
+    let repl = repl()?;
+    assert_eq!(repl.eval("1 + 2, 'a'")?, "3\ta");
+    assert_eq!(repl.eval("x = 5")?, "");
+    assert_eq!(repl.eval("x")?, "5");
+    assert_eq!(repl.eval("game.player:get_name()")?, "steve");
+    assert!(repl.eval("error('oops')").is_err());
+    Ok(())
+}
+
+#[test]
+fn complete() -> Result<()> {
+    let repl = repl()?;
+    assert_eq!(names(&repl, "ga"), ["game"]);
+    assert!(names(&repl, "").contains(&"spawn".to_owned()));
+    assert_eq!(names(&repl, "game.pl"), ["player"]);
+    assert_eq!(
+        names(&repl, "game.player:get"),
+        ["get_name", "get_position"]
+    );
+    assert_eq!(names(&repl, "game.player."), ["velocity"]);
+    assert_eq!(
+        names(&repl, "print(game.player:get_position()."),
+        ["unit", "x", "y"]
+    );
+    assert_eq!(names(&repl, "spawn().velocity:n"), ["normalized"]);
+    assert!(names(&repl, "unknown.").is_empty());
+
+    let completions = repl.complete("local a = game.player:get_p");
+    assert_eq!(completions.start, "local a = game.player:".len());
+    assert_eq!(completions.candidates[0].kind, CompletionKind::Method);
+    Ok(())
+}
+
+#[test]
+fn describe() -> Result<()> {
+    let repl = repl()?;
+    let player = repl.describe("game.player").unwrap();
+    assert_eq!(player.kind, CompletionKind::Global);
+    assert!(player
+        .doc
+        .starts_with("The player that is currently playing"));
+
+    let length = repl.describe("game.player.velocity:length").unwrap();
+    assert!(length.signature.contains("number"));
+    assert!(length.doc.contains("The length of the vector"));
+    assert!(repl.describe("game.player.health").is_none());
+    Ok(())
+}
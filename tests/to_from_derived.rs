@@ -1,14 +1,22 @@
+use std::{collections::HashMap, path::PathBuf};
+
 use tealr::{
-    mlu::{mlua::Lua, FromToLua},
-    ToTypename, TypeWalker,
+    mlu::{
+        mlua::{FromLua, IntoLua, Lua, UserDataRef, Value},
+        FromLuaExact, FromToLua, TealData, TealDataMethods, TypedFunction, UserData,
+    },
+    type_to_string, EnumRepresentation, TaggedUnionGenerator, ToTypename, TypeBody, TypeGenerator,
+    TypeWalker, Union,
 };
 
 #[derive(FromToLua, ToTypename, PartialEq, Debug, Clone)]
 enum ExampleCStyleEnum {
     This,
+    ///the second word
     Is,
     A,
     Basic,
+    #[tealr(rename = "example")]
     Example,
 }
 
@@ -105,3 +113,510 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn c_enum_rename() -> Result<(), Box<dyn std::error::Error>> {
+    let lua = Lua::new();
+    lua.globals().set("value", ExampleCStyleEnum::Example)?;
+    let value: String = lua.load("return value").eval()?;
+    assert_eq!(value, "example");
+    let value: ExampleCStyleEnum = lua.load("return 'example'").eval()?;
+    assert_eq!(value, ExampleCStyleEnum::Example);
+    assert!(lua
+        .load("return 'Example'")
+        .eval::<ExampleCStyleEnum>()
+        .is_err());
+    Ok(())
+}
+
+fn eval<T: FromLuaExact>(lua: &Lua, code: &str) -> mlua::Result<T> {
+    let value = lua.load(code).eval::<Value>()?;
+    T::from_lua_exact(value, lua)
+}
+
+fn round_trip<T: IntoLua + FromLua>(lua: &Lua, value: T) -> mlua::Result<T> {
+    lua.globals().set("value", value)?;
+    lua.load("return value").eval()
+}
+
+fn tagged_union<T: TypeBody>() -> TaggedUnionGenerator {
+    match T::get_type_body() {
+        TypeGenerator::TaggedUnion(x) => x,
+        _ => panic!("expected a tagged union"),
+    }
+}
+
+///A shape read from a config file
+#[derive(Clone, Debug, PartialEq, FromToLua, ToTypename, FromLuaExact)]
+#[tealr(tagged = "kind")]
+enum Shape {
+    ///A circle around the origin
+    #[tealr(rename = "circle")]
+    Circle {
+        ///the radius of the circle
+        radius: f64,
+    },
+    #[tealr(rename = "rect")]
+    Rect {
+        w: i64,
+        h: i64,
+    },
+    Empty,
+}
+
+#[derive(Clone, Debug, PartialEq, FromToLua, ToTypename, FromLuaExact)]
+#[tealr(externally_tagged)]
+enum External {
+    Click { x: i64, y: i64 },
+    Key(String),
+    Quit,
+}
+
+#[derive(Clone, Debug, PartialEq, FromToLua, ToTypename, FromLuaExact)]
+#[tealr(adjacently_tagged = "t", content = "c")]
+enum Adjacent {
+    Click {
+        x: i64,
+        y: i64,
+    },
+    #[tealr(rename = "key")]
+    Key(String),
+    Quit,
+}
+
+#[derive(Clone, Debug, PartialEq, FromToLua, ToTypename, FromLuaExact)]
+#[tealr(untagged)]
+enum Untagged {
+    Click { x: i64, y: i64 },
+    Key(String),
+    Count(i64),
+    Quit,
+}
+
+#[test]
+fn internally_tagged() -> mlua::Result<()> {
+    let lua = Lua::new();
+    let shape: Shape = lua.load("return { kind = 'rect', w = 1, h = 2 }").eval()?;
+    assert_eq!(shape, Shape::Rect { w: 1, h: 2 });
+    let shape: Shape = lua.load("return { kind = 'Empty' }").eval()?;
+    assert_eq!(shape, Shape::Empty);
+
+    lua.globals().set("shape", Shape::Circle { radius: 2.5 })?;
+    lua.load("assert(shape.kind == 'circle' and shape.radius == 2.5)")
+        .exec()?;
+
+    let error = lua
+        .load("return { kind = 'triangle' }")
+        .eval::<Shape>()
+        .unwrap_err();
+    assert!(
+        error.to_string().contains("unknown kind `triangle`"),
+        "{}",
+        error
+    );
+
+    let union = tagged_union::<Shape>();
+    assert_eq!(
+        union.representation,
+        EnumRepresentation::InternallyTagged("kind".to_owned())
+    );
+    assert_eq!(union.type_doc, "A shape read from a config file\n\n");
+    let tags: Vec<_> = union
+        .variants
+        .iter()
+        .map(|v| v.tag_value.as_str())
+        .collect();
+    assert_eq!(tags, ["circle", "rect", "Empty"]);
+    let circle = &union.variants[0];
+    assert_eq!(circle.name, "ShapeCircle");
+    assert_eq!(circle.doc, "A circle around the origin\n");
+    assert_eq!(
+        circle.documentation.get(&circle.fields[0].name).unwrap(),
+        "the radius of the circle\n"
+    );
+    assert_eq!(
+        union.to_teal(),
+        "record ShapeCircle where self.kind == \"circle\"\n   kind: string\n   radius: number\nend\n\
+        record ShapeRect where self.kind == \"rect\"\n   kind: string\n   w: integer\n   h: integer\nend\n\
+        record ShapeEmpty where self.kind == \"Empty\"\n   kind: string\nend\n\
+        type Shape = ShapeCircle | ShapeRect | ShapeEmpty\n"
+    );
+    assert_eq!(
+        union.to_luau(),
+        "type ShapeCircle = { kind: \"circle\", radius: number }\n\
+        type ShapeRect = { kind: \"rect\", w: number, h: number }\n\
+        type ShapeEmpty = { kind: \"Empty\" }\n\
+        type Shape = ShapeCircle | ShapeRect | ShapeEmpty\n"
+    );
+
+    let walker = TypeWalker::new().process_type::<Shape>();
+    let pages = walker.to_markdown();
+    let page = pages
+        .iter()
+        .find(|v| v.file_name == "Shape.md")
+        .expect("a page for Shape");
+    assert!(page.content.contains("## ShapeCircle"), "{}", page.content);
+    assert!(page.content.contains("### radius"), "{}", page.content);
+    assert!(page.content.contains("the radius of the circle"));
+    assert!(walker.to_json().unwrap().contains("\"TaggedUnion\""));
+    Ok(())
+}
+
+#[test]
+fn externally_tagged() -> mlua::Result<()> {
+    let lua = Lua::new();
+    lua.globals().set("click", External::Click { x: 1, y: 2 })?;
+    lua.globals().set("key", External::Key("a".into()))?;
+    lua.globals().set("quit", External::Quit)?;
+    lua.load(
+        "assert(click.Click.x == 1 and click.Click.y == 2)
+        assert(key.Key == 'a')
+        assert(quit == 'Quit')",
+    )
+    .exec()?;
+    for value in [
+        External::Click { x: 1, y: 2 },
+        External::Key("a".into()),
+        External::Quit,
+    ] {
+        assert_eq!(round_trip(&lua, value.clone())?, value);
+    }
+    assert!(lua
+        .load("return { Key = 'a', Quit = true }")
+        .eval::<External>()
+        .is_err());
+    assert!(lua.load("return 'Stop'").eval::<External>().is_err());
+
+    let union = tagged_union::<External>();
+    assert_eq!(
+        union.to_teal(),
+        "record ExternalClick where self.Click ~= nil\n   record Content\n      x: integer\n      y: integer\n   end\n   Click: Content\nend\n\
+        record ExternalKey where self.Key ~= nil\n   Key: string\nend\n\
+        type External = ExternalClick | ExternalKey | string\n"
+    );
+    assert_eq!(
+        union.to_luau(),
+        "type ExternalClick = { Click: { x: number, y: number } }\n\
+        type ExternalKey = { Key: string }\n\
+        type External = ExternalClick | ExternalKey | \"Quit\"\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn adjacently_tagged() -> mlua::Result<()> {
+    let lua = Lua::new();
+    lua.globals().set("click", Adjacent::Click { x: 1, y: 2 })?;
+    lua.globals().set("key", Adjacent::Key("a".into()))?;
+    lua.globals().set("quit", Adjacent::Quit)?;
+    lua.load(
+        "assert(click.t == 'Click' and click.c.x == 1 and click.c.y == 2)
+        assert(key.t == 'key' and key.c == 'a')
+        assert(quit.t == 'Quit' and quit.c == nil)",
+    )
+    .exec()?;
+    for value in [
+        Adjacent::Click { x: 1, y: 2 },
+        Adjacent::Key("a".into()),
+        Adjacent::Quit,
+    ] {
+        assert_eq!(round_trip(&lua, value.clone())?, value);
+    }
+
+    let union = tagged_union::<Adjacent>();
+    assert_eq!(
+        union.representation,
+        EnumRepresentation::AdjacentlyTagged("t".to_owned(), "c".to_owned())
+    );
+    assert_eq!(
+        union.to_teal(),
+        "record AdjacentClick where self.t == \"Click\"\n   record Content\n      x: integer\n      y: integer\n   end\n   t: string\n   c: Content\nend\n\
+        record AdjacentKey where self.t == \"key\"\n   t: string\n   c: string\nend\n\
+        record AdjacentQuit where self.t == \"Quit\"\n   t: string\nend\n\
+        type Adjacent = AdjacentClick | AdjacentKey | AdjacentQuit\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn untagged() -> mlua::Result<()> {
+    let lua = Lua::new();
+    lua.globals().set("click", Untagged::Click { x: 1, y: 2 })?;
+    lua.globals().set("count", Untagged::Count(3))?;
+    lua.globals().set("quit", Untagged::Quit)?;
+    lua.load("assert(click.x == 1 and count == 3 and quit == nil)")
+        .exec()?;
+    for value in [
+        Untagged::Click { x: 1, y: 2 },
+        Untagged::Key("a".into()),
+        Untagged::Count(3),
+        Untagged::Quit,
+    ] {
+        assert_eq!(round_trip(&lua, value.clone())?, value);
+    }
+    let error = lua
+        .load("return { x = 1, y = 2, z = 3 }")
+        .eval::<Untagged>()
+        .unwrap_err();
+    assert!(
+        error.to_string().contains("none of the variants matched"),
+        "{}",
+        error
+    );
+
+    let union = tagged_union::<Untagged>();
+    assert_eq!(
+        union.to_teal(),
+        "record UntaggedClick\n   x: integer\n   y: integer\nend\n\
+        type Untagged = UntaggedClick | string | integer\n"
+    );
+    assert_eq!(
+        union.to_luau(),
+        "type UntaggedClick = { x: number, y: number }\n\
+        type Untagged = UntaggedClick | string | number\n"
+    );
+    Ok(())
+}
+
+#[derive(Union)]
+enum Arg {
+    Text(String),
+    Map(HashMap<String, i64>),
+    List(Vec<String>),
+    Missing(Option<bool>),
+}
+
+#[derive(Union)]
+enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+#[derive(Clone, UserData, ToTypename)]
+struct Unions {}
+
+impl TealData for Unions {
+    fn add_methods<T: TealDataMethods<Self>>(methods: &mut T) {
+        methods.add_method("describe", |_, _, arg: Arg| {
+            Ok(match arg {
+                Arg::Text(x) => x,
+                Arg::List(x) => x.join(","),
+                Arg::Map(x) => format!("{} keys", x.len()),
+                Arg::Missing(x) => format!("{:?}", x),
+            })
+        });
+        methods.add_method("either", |_, _, x: Either<i64, Vec<i64>>| {
+            Ok(match x {
+                Either::Left(x) => Either::Left(x * 2),
+                Either::Right(x) => Either::Right(x.into_iter().sum::<i64>()),
+            })
+        });
+    }
+}
+
+#[test]
+fn union() -> mlua::Result<()> {
+    assert_eq!(
+        type_to_string(&Arg::to_typename(), false),
+        "(string | {string : integer} | {string} | boolean)"
+    );
+    assert_eq!(
+        type_to_string(&Either::<i64, Vec<i64>>::to_typename(), false),
+        "(integer | {integer})"
+    );
+
+    let lua = Lua::new();
+    lua.globals().set("unions", Unions {})?;
+    let code = "
+        assert(unions:describe('hi') == 'hi')
+        assert(unions:describe({'a', 'b'}) == 'a,b')
+        assert(unions:describe({a = 1, b = 2}) == '2 keys')
+        assert(unions:describe(nil) == 'None')
+        assert(unions:describe(true) == 'Some(true)')
+        assert(unions:either(2) == 4)
+        return unions:either({1, 2, 3})
+    ";
+    let res: i64 = lua.load(code).eval()?;
+    assert_eq!(res, 6);
+
+    let error = match lua.load("return 1.5").eval::<Arg>() {
+        Ok(_) => panic!("a number is not part of the union"),
+        Err(x) => x.to_string(),
+    };
+    assert!(error.contains("none of the alternatives matched"));
+    for alternative in ["string:", "{string}:", "{string : integer}:", "boolean:"] {
+        assert!(
+            error.contains(alternative),
+            "{} misses {}",
+            error,
+            alternative
+        );
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug, PartialEq, FromToLua, ToTypename, FromLuaExact)]
+struct Point {
+    x: i64,
+    y: i64,
+    label: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, FromToLua, ToTypename, FromLuaExact)]
+struct Pair(i64, String);
+
+#[derive(Clone, Debug, PartialEq, FromToLua, ToTypename, FromLuaExact)]
+enum Direction {
+    Up,
+    Down,
+}
+
+#[derive(Clone, Debug, PartialEq, FromToLua, ToTypename, FromLuaExact)]
+enum Wrapped {
+    Number(i64),
+}
+
+#[derive(Clone, Debug, PartialEq, UserData, ToTypename, FromLuaExact)]
+#[tealr(userdata)]
+struct Counter {
+    count: i64,
+}
+
+impl TealData for Counter {
+    fn add_methods<T: TealDataMethods<Self>>(methods: &mut T) {
+        methods.add_function("new", |_, count: i64| Ok(Counter { count }));
+    }
+}
+
+//only one of the alternatives matches the exact shape of a value
+#[derive(Union)]
+enum Exact {
+    Point(Point),
+    Pair(Pair),
+    Direction(Direction),
+    Wrapped(Wrapped),
+    Counter(Counter),
+    Shape(Shape),
+    Name(String),
+}
+
+#[test]
+fn from_lua_exact() -> mlua::Result<()> {
+    let lua = Lua::new();
+    lua.globals()
+        .set("Counter", tealr::mlu::UserDataProxy::<Counter>::new(&lua)?)?;
+    lua.globals().set("Wrapped", WrappedCreator::new())?;
+
+    assert!(matches!(
+        eval::<Exact>(&lua, "return {x = 1, y = 2}")?,
+        Exact::Point(Point {
+            x: 1,
+            y: 2,
+            label: None
+        })
+    ));
+    assert!(matches!(
+        eval::<Exact>(&lua, "return {[0] = 1, [1] = 'a'}")?,
+        Exact::Pair(Pair(1, x)) if x == "a"
+    ));
+    assert!(matches!(
+        eval::<Exact>(&lua, "return 'Up'")?,
+        Exact::Direction(Direction::Up)
+    ));
+    assert!(matches!(
+        eval::<Exact>(&lua, "return Wrapped.NewNumberFrom(3)")?,
+        Exact::Wrapped(Wrapped::Number(3))
+    ));
+    assert!(matches!(
+        eval::<Exact>(&lua, "return Counter.new(4)")?,
+        Exact::Counter(Counter { count: 4 })
+    ));
+    assert!(matches!(
+        eval::<Exact>(&lua, "return { kind = 'circle', radius = 1.5 }")?,
+        Exact::Shape(Shape::Circle { radius }) if radius == 1.5
+    ));
+    assert!(matches!(
+        eval::<Exact>(&lua, "return 'circle'")?,
+        Exact::Name(_)
+    ));
+
+    assert!(eval::<Point>(&lua, "return {x = 1, y = 2, z = 3}").is_err());
+    assert!(eval::<Point>(&lua, "return {x = 1, y = 2.5}").is_err());
+    assert!(eval::<Pair>(&lua, "return {[0] = 1}").is_err());
+    assert!(eval::<Direction>(&lua, "return 'Left'").is_err());
+    assert!(eval::<Direction>(&lua, "return 1").is_err());
+    assert!(eval::<Counter>(&lua, "return {count = 4}").is_err());
+    assert!(eval::<Exact>(&lua, "return { kind = 'rect', w = 1, h = 2.5 }").is_err());
+    assert!(eval::<Exact>(&lua, "return { kind = 'rect', w = 1, h = 2, d = 3 }").is_err());
+    assert!(eval::<Exact>(&lua, "return { kind = 'triangle' }").is_err());
+    assert!(eval::<Exact>(&lua, "return { w = 1, h = 2 }").is_err());
+
+    assert_eq!(
+        eval::<Adjacent>(&lua, "return { t = 'key', c = 'a' }")?,
+        Adjacent::Key("a".into())
+    );
+    assert!(eval::<Adjacent>(&lua, "return { t = 'key', c = 1 }").is_err());
+    assert!(eval::<Adjacent>(&lua, "return { t = 'key', c = 'a', d = 1 }").is_err());
+    assert!(eval::<Adjacent>(&lua, "return { t = 'Quit', c = 1 }").is_err());
+    assert!(eval::<Adjacent>(&lua, "return { t = 'Click', c = { x = 1, y = 2.5 } }").is_err());
+    assert_eq!(
+        eval::<External>(&lua, "return { Click = { x = 1, y = 2 } }")?,
+        External::Click { x: 1, y: 2 }
+    );
+    assert!(eval::<External>(&lua, "return { Click = { x = 1 }, Key = 'a' }").is_err());
+    Ok(())
+}
+
+#[test]
+fn from_lua_exact_errors() {
+    let lua = Lua::new();
+    let error = eval::<Point>(&lua, "return {x = 1, y = 'no'}").unwrap_err();
+    assert!(error.to_string().contains("field `y`"), "{}", error);
+    let error = eval::<Point>(&lua, "return {x = 1, y = 2, z = 3}").unwrap_err();
+    assert!(
+        error.to_string().contains("unexpected key `z`"),
+        "{}",
+        error
+    );
+}
+
+#[derive(Union)]
+enum Argument {
+    Callback(TypedFunction<i64, i64>),
+    Path(PathBuf),
+    Other(Value),
+}
+
+#[test]
+fn from_lua_exact_other_types() -> mlua::Result<()> {
+    let lua = Lua::new();
+    lua.globals()
+        .set("Counter", tealr::mlu::UserDataProxy::<Counter>::new(&lua)?)?;
+
+    assert_eq!(
+        eval::<UserDataRef<Counter>>(&lua, "return Counter.new(1)")?.count,
+        1
+    );
+    assert!(eval::<UserDataRef<Counter>>(&lua, "return {count = 1}").is_err());
+    match eval::<Argument>(&lua, "return function(x) return x + 1 end")? {
+        Argument::Callback(x) => assert_eq!(x.call(1)?, 2),
+        _ => panic!("expected a function"),
+    }
+    assert!(matches!(
+        eval::<Argument>(&lua, "return 'some/path'")?,
+        Argument::Path(x) if x.to_str() == Some("some/path")
+    ));
+    assert!(matches!(
+        eval::<Argument>(&lua, "return 1")?,
+        Argument::Other(Value::Integer(1))
+    ));
+
+    assert_eq!(
+        eval::<(i64, String)>(&lua, "return {1, 'a'}")?,
+        (1, "a".to_string())
+    );
+    assert!(eval::<(i64, String)>(&lua, "return {1, 'a', 2}").is_err());
+    assert!(eval::<(i64, String)>(&lua, "return {1, 'a', x = 2}").is_err());
+    assert_eq!(eval::<bstr::BString>(&lua, "return 'bytes'")?, "bytes");
+    Ok(())
+}